@@ -0,0 +1,7 @@
+use gloo::worker::Registrable;
+
+use proof_viz::worker::VerifierWorker;
+
+fn main() {
+    VerifierWorker::registrar().register();
+}
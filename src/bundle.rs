@@ -0,0 +1,248 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use elliptic_curve::pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::Element;
+
+use crate::export::download;
+
+/// Id of the element that holds the proof in an offline bundle
+const BUNDLE_ID: &str = "proof-viz-bundle";
+/// Name of the app binary, its JS glue and wasm files start with it
+const APP_NAME: &str = "proof_viz";
+
+/// What an offline bundle embeds besides the app
+#[derive(Serialize, Deserialize)]
+struct Embedded {
+    name: String,
+    /// The proof file, base64 encoded
+    proof: String,
+    /// PEM of the Notary public key
+    notary_key: String,
+}
+
+/// A proof and Notary key embedded in an offline bundle
+pub struct Bundle {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub key: p256::PublicKey,
+}
+
+/// Progress of saving an offline bundle
+#[derive(Clone, Debug, PartialEq)]
+pub enum BundleExport {
+    Building,
+    Saved,
+    Failed(String),
+}
+
+/// The proof embedded in the page, when the app was opened from an offline bundle
+pub fn embedded() -> Option<Result<Bundle, String>> {
+    let element = gloo::utils::document().get_element_by_id(BUNDLE_ID)?;
+    let text = element.text_content().unwrap_or_default();
+    Some(
+        serde_json::from_str::<Embedded>(&text)
+            .map_err(|err| err.to_string())
+            .and_then(|embedded| {
+                Ok(Bundle {
+                    data: STANDARD
+                        .decode(&embedded.proof)
+                        .map_err(|err| format!("Invalid proof: {}", err))?,
+                    key: p256::PublicKey::from_public_key_pem(&embedded.notary_key)
+                        .map_err(|err| format!("Invalid Notary public key: {}", err))?,
+                    name: embedded.name,
+                })
+            })
+            .map_err(|err| format!("The offline bundle is damaged: {}", err)),
+    )
+}
+
+/// `href`s of the links and URLs quoted in the inline scripts of the page
+fn asset_urls() -> Vec<String> {
+    let document = gloo::utils::document();
+    let elements = |selector: &str| -> Vec<Element> {
+        document
+            .query_selector_all(selector)
+            .ok()
+            .map(|nodes| {
+                (0..nodes.length())
+                    .filter_map(|i| nodes.item(i)?.dyn_into::<Element>().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut urls: Vec<String> = elements("link[href]")
+        .iter()
+        .filter_map(|link| link.get_attribute("href"))
+        .collect();
+    // The script trunk adds to load the app
+    for script in elements("script:not([src])") {
+        let text = script.text_content().unwrap_or_default();
+        urls.extend(
+            text.split(['\'', '"'])
+                .skip(1)
+                .step_by(2)
+                .map(|quoted| quoted.to_string()),
+        );
+    }
+    urls
+}
+
+fn is_app_file(url: &str, suffix: &str) -> bool {
+    let file = url.split(['?', '#']).next().unwrap_or(url);
+    let file = file.rsplit('/').next().unwrap_or(file);
+    file.starts_with(APP_NAME) && file.ends_with(suffix)
+}
+
+async fn fetch(url: &str) -> Result<gloo::net::http::Response, String> {
+    let response = gloo::net::http::Request::get(url)
+        .send()
+        .await
+        .map_err(|err| format!("Downloading {} failed: {}", url, err))?;
+    if !response.ok() {
+        return Err(format!(
+            "Downloading {} failed: {} {}",
+            url,
+            response.status(),
+            response.status_text()
+        ));
+    }
+    Ok(response)
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    fetch(url)
+        .await?
+        .text()
+        .await
+        .map_err(|err| format!("Downloading {} failed: {}", url, err))
+}
+
+/// Keep embedded text from closing the raw text element `tag` it is in, like `style`.
+///
+/// Only the end tag of the element is escaped, in any case: `<\/` is the same in JS and JSON
+/// strings and in CSS strings, but elsewhere in CSS it breaks the rule it is in.
+fn escape_raw_text(text: &str, tag: &str) -> String {
+    let end_tag = format!("</{}", tag);
+    let mut escaped = String::with_capacity(text.len());
+    let mut last = 0;
+    // ASCII lowercasing keeps the byte offsets
+    for (pos, _) in text.to_ascii_lowercase().match_indices(&end_tag) {
+        escaped.push_str(&text[last..pos + 1]);
+        escaped.push('\\');
+        last = pos + 1;
+    }
+    escaped.push_str(&text[last..]);
+    escaped
+}
+
+/// Build a single HTML file with the app, the proof `data` and the Notary `key`, that verifies
+/// the proof again when it is opened, without network access.
+///
+/// The app files are downloaded again from where the app is served, they are usually cached.
+async fn build(name: &str, data: &[u8], key: &p256::PublicKey) -> Result<String, String> {
+    let urls = asset_urls();
+    let not_found = || {
+        "The app files were not found, offline bundles can only be saved from the hosted app"
+            .to_string()
+    };
+    let glue_url = urls
+        .iter()
+        .find(|url| is_app_file(url, ".js"))
+        .ok_or_else(not_found)?;
+    let wasm_url = urls
+        .iter()
+        .find(|url| is_app_file(url, "_bg.wasm"))
+        .ok_or_else(not_found)?;
+
+    let glue = fetch_text(glue_url).await?;
+    if glue.contains("./snippets/") {
+        return Err("The app imports JS snippets, which can not be bundled".to_string());
+    }
+    let wasm = fetch(wasm_url)
+        .await?
+        .binary()
+        .await
+        .map_err(|err| format!("Downloading {} failed: {}", wasm_url, err))?;
+
+    let mut styles = String::new();
+    let document = gloo::utils::document();
+    if let Ok(links) = document.query_selector_all("link[rel=stylesheet][href]") {
+        for i in 0..links.length() {
+            let href = links
+                .item(i)
+                .and_then(|node| node.dyn_into::<Element>().ok())
+                .and_then(|link| link.get_attribute("href"));
+            if let Some(href) = href {
+                styles.push_str(&fetch_text(&href).await?);
+            }
+        }
+    }
+
+    let embedded = Embedded {
+        name: name.to_string(),
+        proof: STANDARD.encode(data),
+        notary_key: key
+            .to_public_key_pem(LineEnding::LF)
+            .map_err(|err| err.to_string())?,
+    };
+    let embedded = serde_json::to_string(&embedded).map_err(|err| err.to_string())?;
+
+    // `__wbg_init` is the init function of the wasm-bindgen glue, which is inlined
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8" />
+<title>TLSNotary proof • {title}</title>
+<style>{styles}</style>
+<script type="application/json" id="{id}">{embedded}</script>
+</head>
+<body>
+<script type="module">
+{glue}
+const wasm = Uint8Array.from(atob("{wasm}"), (c) => c.charCodeAt(0));
+await __wbg_init(wasm);
+</script>
+</body>
+</html>
+"#,
+        title = name.replace('&', "&amp;").replace('<', "&lt;"),
+        styles = escape_raw_text(&styles, "style"),
+        id = BUNDLE_ID,
+        embedded = escape_raw_text(&embedded, "script"),
+        glue = escape_raw_text(&glue, "script"),
+        wasm = STANDARD.encode(wasm),
+    ))
+}
+
+/// Build an offline bundle and let the browser save it
+pub async fn save(name: String, data: Vec<u8>, key: p256::PublicKey) -> Result<(), String> {
+    let html = build(&name, &data, &key).await?;
+    let stem = name.strip_suffix(".json").unwrap_or(&name);
+    download(&format!("{}.bundle.html", stem), "text/html", &html);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_end_tags_only() {
+        // Other `</` in style sheets are kept
+        let css = "a{}/* </p> */.x::after{content:\"</STYLE>\"}";
+        assert_eq!(
+            escape_raw_text(css, "style"),
+            "a{}/* </p> */.x::after{content:\"<\\/STYLE>\"}"
+        );
+        assert_eq!(
+            escape_raw_text("s = \"</Script></div>\"; t = \"</script\";", "script"),
+            "s = \"<\\/Script></div>\"; t = \"<\\/script\";"
+        );
+        assert_eq!(escape_raw_text("é</style", "script"), "é</style");
+        assert_eq!(escape_raw_text("", "style"), "");
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x509_cert::der::Decode;
+use x509_cert::Certificate;
+
+use crate::verify::hex;
+
+/// A certificate of the chain the server presented in the TLS handshake
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    /// SHA-256 of the DER encoding, hex encoded
+    pub fingerprint: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+}
+
+impl CertificateInfo {
+    fn new(der: &[u8]) -> Self {
+        let fingerprint = hex(&Sha256::digest(der));
+        match Certificate::from_der(der) {
+            Ok(certificate) => {
+                let tbs = certificate.tbs_certificate;
+                Self {
+                    fingerprint,
+                    subject: tbs.subject.to_string(),
+                    issuer: tbs.issuer.to_string(),
+                    not_before: tbs.validity.not_before.to_string(),
+                    not_after: tbs.validity.not_after.to_string(),
+                }
+            }
+            // The chain was checked during verification, this only describes it
+            Err(_) => Self {
+                fingerprint,
+                subject: String::new(),
+                issuer: String::new(),
+                not_before: String::new(),
+                not_after: String::new(),
+            },
+        }
+    }
+}
+
+/// Read the server certificate chain, leaf first, from a `proof.json`.
+///
+/// The chain is part of the handshake data that `SessionProof::verify` checks against the
+/// session header, it is read from the proof file because the proof types keep it private.
+pub fn read_certificates(proof: &serde_json::Value) -> Vec<CertificateInfo> {
+    let Some(chain) = proof["session"]["handshake_data_decommitment"]["data"]
+        ["server_cert_details"]["cert_chain"]
+        .as_array()
+    else {
+        return Vec::new();
+    };
+
+    chain
+        .iter()
+        .filter_map(|certificate| {
+            let der: Option<Vec<u8>> = certificate
+                .as_array()?
+                .iter()
+                .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                .collect();
+            Some(CertificateInfo::new(&der?))
+        })
+        .collect()
+}
@@ -0,0 +1,294 @@
+use std::ops::Range;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+// Browsers only look for a `<meta charset>` in the first 1024 bytes of a document
+const META_SNIFF_LEN: usize = 1024;
+
+/// Decodes HTTP transcripts according to the charset of the message body.
+///
+/// The start line and headers are always decoded as UTF-8. The body charset is taken from a
+/// byte order mark, the `charset` parameter of the `Content-Type` header or an HTML
+/// `<meta charset>` (in that order), and defaults to UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TranscriptDecoder {
+    body_start: usize,
+    bom_len: usize,
+    encoding: &'static Encoding,
+}
+
+impl TranscriptDecoder {
+    pub fn new(bytes: &[u8]) -> Self {
+        let body_start = find(bytes, b"\r\n\r\n").map_or(bytes.len(), |pos| pos + 4);
+        let body = &bytes[body_start..];
+        let content_type = header_value(&bytes[..body_start], b"content-type");
+
+        if let Some((encoding, bom_len)) = Encoding::for_bom(body) {
+            return Self {
+                body_start,
+                bom_len,
+                encoding,
+            };
+        }
+
+        let encoding = content_type
+            .as_deref()
+            .and_then(charset_param)
+            .or_else(|| {
+                let is_html = content_type
+                    .as_deref()
+                    .map_or(true, |value| find(value, b"html").is_some());
+                if is_html {
+                    // A `<meta>` tag can not declare UTF-16, it would not have been readable as ASCII
+                    meta_charset(body).map(|encoding| {
+                        if encoding == UTF_16LE || encoding == UTF_16BE {
+                            UTF_8
+                        } else {
+                            encoding
+                        }
+                    })
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(UTF_8);
+
+        Self {
+            body_start,
+            bom_len: 0,
+            encoding,
+        }
+    }
+
+    /// Offset of the first body byte in the transcript.
+    pub fn body_start(&self) -> usize {
+        self.body_start
+    }
+
+    /// Decode the bytes in `range` into characters paired with the offset of their first byte.
+    ///
+    /// The head and the body are decoded with their own charset. Ranges are decoded
+    /// independently, so callers can decode the parts of a transcript in between redactions.
+    pub fn decode(&self, bytes: &[u8], range: Range<usize>) -> Vec<(char, usize)> {
+        let range = range.start.min(bytes.len())..range.end.min(bytes.len());
+        let split = self.body_start.clamp(range.start, range.end);
+
+        let mut chars = decode_with_offsets(UTF_8, bytes, range.start..split);
+
+        // The byte order mark is not part of the content
+        let body_start = split.max(self.body_start + self.bom_len).min(range.end);
+        chars.extend(decode_with_offsets(
+            self.encoding,
+            bytes,
+            body_start..range.end,
+        ));
+
+        chars
+    }
+}
+
+/// Decode byte by byte, so every character can be traced back to the bytes it came from.
+fn decode_with_offsets(
+    encoding: &'static Encoding,
+    bytes: &[u8],
+    range: Range<usize>,
+) -> Vec<(char, usize)> {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut chars = Vec::with_capacity(range.len());
+    let mut text = String::new();
+    let mut char_start = range.start;
+
+    for pos in range.clone() {
+        text.clear();
+        text.reserve(decoder.max_utf8_buffer_length(1).unwrap_or(8));
+        let last = pos + 1 == range.end;
+        // A single byte never overflows the reserved buffer
+        let _ = decoder.decode_to_string(&bytes[pos..pos + 1], &mut text, last);
+        if !text.is_empty() {
+            chars.extend(text.chars().map(|c| (c, char_start)));
+            char_start = pos + 1;
+        }
+    }
+
+    chars
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |pos| pos + 1);
+    &bytes[start..end]
+}
+
+/// Lowercased value of the first header called `name` (which must be lowercase).
+fn header_value(head: &[u8], name: &[u8]) -> Option<Vec<u8>> {
+    head.split(|&b| b == b'\n').skip(1).find_map(|line| {
+        let colon = line.iter().position(|&b| b == b':')?;
+        if trim(&line[..colon]).eq_ignore_ascii_case(name) {
+            Some(trim(&line[colon + 1..]).to_ascii_lowercase())
+        } else {
+            None
+        }
+    })
+}
+
+/// Encoding named by a `charset=` parameter in an (already lowercased) header or tag.
+fn charset_param(value: &[u8]) -> Option<&'static Encoding> {
+    let pos = find(value, b"charset=")?;
+    let label: Vec<u8> = value[pos + b"charset=".len()..]
+        .iter()
+        .skip_while(|&&b| b == b'"' || b == b'\'')
+        .take_while(|&&b| {
+            !matches!(b, b'"' | b'\'' | b';' | b'>' | b'/') && !b.is_ascii_whitespace()
+        })
+        .copied()
+        .collect();
+    Encoding::for_label(&label)
+}
+
+fn meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let prefix = body[..body.len().min(META_SNIFF_LEN)].to_ascii_lowercase();
+
+    let mut rest = prefix.as_slice();
+    while let Some(pos) = find(rest, b"<meta") {
+        let tag = &rest[pos..];
+        let end = tag.iter().position(|&b| b == b'>').unwrap_or(tag.len());
+        if let Some(encoding) = charset_param(&tag[..end]) {
+            return Some(encoding);
+        }
+        rest = &tag[end..];
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+
+    fn response(content_type: Option<&str>, body: &[u8]) -> Vec<u8> {
+        let mut bytes = b"HTTP/1.1 200 OK\r\n".to_vec();
+        if let Some(content_type) = content_type {
+            bytes.extend(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        bytes.extend(b"\r\n");
+        bytes.extend(body);
+        bytes
+    }
+
+    fn text(decoder: &TranscriptDecoder, bytes: &[u8], range: Range<usize>) -> String {
+        decoder
+            .decode(bytes, range)
+            .into_iter()
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    #[test]
+    fn defaults_to_utf8() {
+        let decoder = TranscriptDecoder::new(&response(Some("text/plain"), b"caf\xc3\xa9"));
+        assert_eq!(decoder.encoding, UTF_8);
+        let decoder = TranscriptDecoder::new(b"GET / HTTP/1.1\r\nHost: a");
+        assert_eq!(decoder.encoding, UTF_8);
+        assert_eq!(decoder.body_start(), 23);
+    }
+
+    #[test]
+    fn bom_wins_over_header_and_meta() {
+        let bytes = response(
+            Some("text/html; charset=windows-1252"),
+            b"\xef\xbb\xbf<meta charset=\"shift_jis\">",
+        );
+        let decoder = TranscriptDecoder::new(&bytes);
+        assert_eq!(decoder.encoding, UTF_8);
+        assert_eq!(decoder.bom_len, 3);
+
+        // The byte order mark is not decoded
+        let body = text(&decoder, &bytes, decoder.body_start()..bytes.len());
+        assert_eq!(body, "<meta charset=\"shift_jis\">");
+    }
+
+    #[test]
+    fn header_wins_over_meta() {
+        let bytes = response(
+            Some("text/html; Charset=\"ISO-8859-1\""),
+            b"<meta charset=shift_jis>\xe9",
+        );
+        let decoder = TranscriptDecoder::new(&bytes);
+        // Browsers treat ISO-8859-1 as windows-1252
+        assert_eq!(decoder.encoding, WINDOWS_1252);
+        assert!(text(&decoder, &bytes, 0..bytes.len()).ends_with('é'));
+    }
+
+    #[test]
+    fn meta_charset_of_html() {
+        let bytes = response(
+            Some("text/html"),
+            b"<meta name=a><META http-equiv=\"Content-Type\" content=\"text/html; charset=Shift_JIS\">",
+        );
+        assert_eq!(TranscriptDecoder::new(&bytes).encoding, SHIFT_JIS);
+
+        // Without a Content-Type the body may be HTML
+        let bytes = response(None, b"<meta charset='windows-1252'>");
+        assert_eq!(TranscriptDecoder::new(&bytes).encoding, WINDOWS_1252);
+    }
+
+    #[test]
+    fn meta_charset_ignored() {
+        // Only HTML declares its charset in a tag
+        let bytes = response(
+            Some("application/json"),
+            b"{\"a\":\"<meta charset=shift_jis>\"}",
+        );
+        assert_eq!(TranscriptDecoder::new(&bytes).encoding, UTF_8);
+
+        // A tag can not declare UTF-16
+        let bytes = response(Some("text/html"), b"<meta charset=utf-16le>");
+        assert_eq!(TranscriptDecoder::new(&bytes).encoding, UTF_8);
+
+        // Browsers only look at the start of the document
+        let mut body = vec![b' '; META_SNIFF_LEN];
+        body.extend(b"<meta charset=shift_jis>");
+        assert_eq!(
+            TranscriptDecoder::new(&response(Some("text/html"), &body)).encoding,
+            UTF_8
+        );
+    }
+
+    #[test]
+    fn decode_keeps_offsets() {
+        let bytes = response(Some("text/plain; charset=utf-16le"), b"\x41\x00\xe9\x00");
+        let decoder = TranscriptDecoder::new(&bytes);
+        let start = decoder.body_start();
+        assert_eq!(
+            decoder.decode(&bytes, start - 2..bytes.len()),
+            [
+                ('\r', start - 2),
+                ('\n', start - 1),
+                ('A', start),
+                ('é', start + 2)
+            ]
+        );
+
+        // A multi-byte character is traced back to its first byte
+        let bytes = response(None, "aé".as_bytes());
+        let decoder = TranscriptDecoder::new(&bytes);
+        let start = decoder.body_start();
+        assert_eq!(
+            decoder.decode(&bytes, start..bytes.len() + 5),
+            [('a', start), ('é', start + 1)]
+        );
+    }
+}
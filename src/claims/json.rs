@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use crate::policy::{http_body, parse_http, PathSegment};
+use crate::verify::Transcript;
+
+/// Walks JSON text without building values, to find where a value is
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn string(&mut self) -> Option<Range<usize>> {
+        let start = self.pos;
+        if self.next()? != b'"' {
+            return None;
+        }
+        loop {
+            match self.next()? {
+                b'\\' => self.pos += 1,
+                b'"' => return Some(start..self.pos),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skip the value at the current position and return its range
+    fn value(&mut self) -> Option<Range<usize>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek()? {
+            b'"' => {
+                self.string()?;
+            }
+            open @ (b'{' | b'[') => {
+                let close = if open == b'{' { b'}' } else { b']' };
+                self.pos += 1;
+                self.skip_whitespace();
+                if self.peek()? == close {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        if open == b'{' {
+                            self.skip_whitespace();
+                            self.string()?;
+                            self.skip_whitespace();
+                            if self.next()? != b':' {
+                                return None;
+                            }
+                        }
+                        self.value()?;
+                        self.skip_whitespace();
+                        match self.next()? {
+                            b',' => continue,
+                            byte if byte == close => break,
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+            _ => {
+                while self.peek().is_some_and(|byte| {
+                    !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace()
+                }) {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+            }
+        }
+        Some(start..self.pos)
+    }
+
+    /// Range of the value at `path` below the value at the current position
+    fn find(&mut self, path: &[PathSegment]) -> Option<Range<usize>> {
+        let Some((segment, rest)) = path.split_first() else {
+            return self.value();
+        };
+        self.skip_whitespace();
+        match (segment, self.next()?) {
+            (PathSegment::Key(key), b'{') => loop {
+                self.skip_whitespace();
+                let name = self.string()?;
+                self.skip_whitespace();
+                if self.next()? != b':' {
+                    return None;
+                }
+                let name: String = serde_json::from_slice(&self.bytes[name]).ok()?;
+                if name == *key {
+                    return self.find(rest);
+                }
+                self.value()?;
+                self.skip_whitespace();
+                if self.next()? != b',' {
+                    return None;
+                }
+            },
+            (PathSegment::Index(index), b'[') => {
+                for _ in 0..*index {
+                    self.value()?;
+                    self.skip_whitespace();
+                    if self.next()? != b',' {
+                        return None;
+                    }
+                }
+                self.find(rest)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Range of the value at `path` in the JSON text `body`
+pub(crate) fn find_value(body: &[u8], path: &[PathSegment]) -> Option<Range<usize>> {
+    Scanner {
+        bytes: body,
+        pos: 0,
+    }
+    .find(path)
+}
+
+/// Transcript byte ranges of the value at `path` of the JSON body of the response in
+/// `transcript`
+pub fn value_ranges(transcript: &Transcript, path: &[PathSegment]) -> Option<Vec<Range<usize>>> {
+    let bytes = &transcript.bytes;
+    let chunks = http_body(bytes, &parse_http(bytes)?)?;
+
+    // The body without chunked transfer encoding, and the transcript offset of each byte
+    let offsets: Vec<usize> = chunks.into_iter().flatten().collect();
+    let body: Vec<u8> = offsets.iter().map(|&offset| bytes[offset]).collect();
+
+    let found = find_value(&body, path)?;
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for &offset in &offsets[found] {
+        match ranges.last_mut() {
+            Some(range) if range.end == offset => range.end += 1,
+            _ => ranges.push(offset..offset + 1),
+        }
+    }
+    Some(ranges)
+}
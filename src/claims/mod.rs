@@ -0,0 +1,276 @@
+use std::ops::Range;
+
+use crate::components::redacted_bytes_component::Direction;
+use crate::policy::{json_value, parse_path, Outcome};
+use crate::request::parse_request;
+use crate::verify::{Transcript, VerifiedProof};
+
+pub(crate) mod json;
+mod services;
+pub mod template;
+
+pub use services::JsonService;
+
+/// Bytes of a transcript that support a claim
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+    pub direction: Direction,
+    /// Usually one range, more when the value is split over chunks of the body
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// A fact a proof supports, in the terms of the service, e.g. "Followers: 42"
+#[derive(Clone, Debug, PartialEq)]
+pub struct Claim {
+    pub label: String,
+    pub value: String,
+    pub source: Source,
+}
+
+/// Why a claim could not be read from a proof, e.g. because its value is redacted
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimError {
+    pub label: String,
+    pub reason: String,
+}
+
+impl ClaimError {
+    fn new(label: &str, outcome: Outcome) -> Self {
+        let reason = match outcome {
+            Outcome::Fail(reason) | Outcome::Undeterminable(reason) => reason,
+            Outcome::Pass => "Not found".to_string(),
+        };
+        Self {
+            label: label.to_string(),
+            reason,
+        }
+    }
+}
+
+/// Reads the claims of the proofs of one service, so reviewers do not have to know the layout
+/// of its responses
+pub trait ClaimExtractor {
+    /// Name of the service, shown on the claims card
+    fn name(&self) -> &str;
+
+    /// Whether a proof of `server_name`, with a first request to `path`, is for this service.
+    /// `path` has no query string, and is `None` when it is redacted or there is no request.
+    fn matches(&self, server_name: &str, path: Option<&str>) -> bool;
+
+    /// The claims the disclosed parts of the proof support, and the ones it does not
+    fn extract(&self, proof: &VerifiedProof) -> Vec<Result<Claim, ClaimError>>;
+}
+
+/// The claim extractors of the known services
+pub struct Registry {
+    extractors: Vec<Box<dyn ClaimExtractor>>,
+}
+
+impl Registry {
+    /// A registry without any extractors
+    pub fn empty() -> Self {
+        Self {
+            extractors: Vec::new(),
+        }
+    }
+
+    /// Add an extractor. Extractors registered first are tried first.
+    pub fn register(&mut self, extractor: impl ClaimExtractor + 'static) {
+        self.extractors.push(Box::new(extractor));
+    }
+
+    /// A registry with the templates the user uploaded, then the services built into the
+    /// app. Templates come first, so they can replace a built-in service.
+    pub fn with_templates(templates: Vec<template::Template>) -> Self {
+        let mut registry = Self::empty();
+        for template in templates {
+            registry.register(template);
+        }
+        registry.register_built_in();
+        registry
+    }
+
+    /// Add the extractors of the services built into the app
+    pub fn register_built_in(&mut self) {
+        for service in services::BUILT_IN {
+            self.register(service.clone());
+        }
+    }
+
+    /// The extractor of the service of the proof
+    pub fn find(&self, proof: &VerifiedProof) -> Option<&dyn ClaimExtractor> {
+        let path = request_path(proof);
+        self.extractors
+            .iter()
+            .find(|extractor| extractor.matches(&proof.server_name, path.as_deref()))
+            .map(|extractor| extractor.as_ref())
+    }
+
+    /// Name of the service of the proof and the claims of the proof, for a known service
+    pub fn claims(
+        &self,
+        proof: &VerifiedProof,
+    ) -> Option<(String, Vec<Result<Claim, ClaimError>>)> {
+        self.find(proof)
+            .map(|extractor| (extractor.name().to_string(), extractor.extract(proof)))
+    }
+}
+
+impl Default for Registry {
+    /// A registry with the extractors of the services built into the app
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_built_in();
+        registry
+    }
+}
+
+// Registries hold code, so they are only equal to themselves
+impl PartialEq for Registry {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+/// Path of the first request of the proof, without the query string. `None` when some of
+/// it is redacted, path patterns would match the redacted bytes as if they had been sent.
+pub fn request_path(proof: &VerifiedProof) -> Option<String> {
+    let path = parse_request(&proof.sent)?.path;
+    if path.redacted {
+        return None;
+    }
+    Some(
+        path.text
+            .split('#')
+            .next()
+            .unwrap_or(&path.text)
+            .to_string(),
+    )
+}
+
+/// A claim for the value at `path` of the JSON body of the response, like `$.data.balance`
+pub fn json_claim(proof: &VerifiedProof, label: &str, path: &str) -> Result<Claim, ClaimError> {
+    let path = parse_path(path).map_err(|err| ClaimError::new(label, Outcome::Fail(err)))?;
+    let value = match json_value(&proof.recv, &path).map_err(|err| ClaimError::new(label, err))? {
+        serde_json::Value::String(text) => text,
+        value => value.to_string(),
+    };
+    let ranges = json::value_ranges(&proof.recv, &path)
+        .ok_or_else(|| ClaimError::new(label, Outcome::Fail("Not found".to_string())))?;
+    Ok(Claim {
+        label: label.to_string(),
+        value,
+        source: Source {
+            direction: Direction::Received,
+            ranges,
+        },
+    })
+}
+
+/// A claim for the bytes in `range` of the response
+fn range_claim(transcript: &Transcript, label: &str, range: Range<usize>) -> Claim {
+    Claim {
+        label: label.to_string(),
+        value: String::from_utf8_lossy(&transcript.bytes[range.clone()]).to_string(),
+        source: Source {
+            direction: Direction::Received,
+            ranges: vec![range],
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(server_name: &str, request: &str, redacted: &[&str]) -> VerifiedProof {
+        VerifiedProof {
+            server_name: server_name.to_string(),
+            time: 0,
+            sent: Transcript::redacting(request, redacted),
+            recv: Transcript::redacting("HTTP/1.1 200 OK\r\n\r\n{}", &[]),
+            openings: vec![],
+            certificates: vec![],
+        }
+    }
+
+    fn service(registry: &Registry, proof: &VerifiedProof) -> Option<String> {
+        registry
+            .find(proof)
+            .map(|extractor| extractor.name().to_string())
+    }
+
+    #[test]
+    fn request_path_without_query() {
+        let with_fragment = proof("swapi.dev", "GET /api/people/1?a=b#c HTTP/1.1\r\n\r\n", &[]);
+        assert_eq!(
+            request_path(&with_fragment),
+            Some("/api/people/1".to_string())
+        );
+
+        // Only the path has to be disclosed
+        let redacted_query = proof(
+            "swapi.dev",
+            "GET /api/people/1?a=b HTTP/1.1\r\n\r\n",
+            &["b"],
+        );
+        assert_eq!(
+            request_path(&redacted_query),
+            Some("/api/people/1".to_string())
+        );
+    }
+
+    #[test]
+    fn redacted_path_does_not_select_a_service() {
+        let registry = Registry::default();
+        let request = "GET /api/people/1 HTTP/1.1\r\n\r\n";
+        assert_eq!(
+            service(&registry, &proof("swapi.dev", request, &[])),
+            Some("Star Wars character".to_string())
+        );
+
+        // The redacted bytes may have been another path
+        let proof = proof("swapi.dev", request, &["people/1"]);
+        assert_eq!(request_path(&proof), None);
+        assert_eq!(service(&registry, &proof), None);
+    }
+
+    #[test]
+    fn redacted_path_does_not_select_a_template() {
+        let template = template::Template::parse(
+            r#"{"name": "Bank", "server_name": "*.bank.example", "path": "^/v1/accounts/[A-Z]+$", "fields": [{"label": "Balance", "json": "$.balance"}]}"#,
+        )
+        .unwrap();
+        let registry = Registry::with_templates(vec![template]);
+
+        // The filler bytes would match the pattern, the path that was sent does not
+        let redacted = proof(
+            "api.bank.example",
+            "GET /v1/accounts/secret HTTP/1.1\r\n\r\n",
+            &["secret"],
+        );
+        assert_eq!(service(&registry, &redacted), None);
+
+        let disclosed = proof(
+            "api.bank.example",
+            "GET /v1/accounts/SAVINGS HTTP/1.1\r\n\r\n",
+            &[],
+        );
+        assert_eq!(service(&registry, &disclosed), Some("Bank".to_string()));
+    }
+
+    #[test]
+    fn template_without_path_ignores_redacted_path() {
+        let template = template::Template::parse(
+            "name = \"Bank\"\nserver_name = \"*.bank.example\"\n\n[[fields]]\nlabel = \"Date\"\nheader = \"Date\"\n",
+        )
+        .unwrap();
+        let registry = Registry::with_templates(vec![template]);
+        let proof = proof(
+            "api.bank.example",
+            "GET /secret HTTP/1.1\r\n\r\n",
+            &["secret"],
+        );
+        assert_eq!(service(&registry, &proof), Some("Bank".to_string()));
+    }
+}
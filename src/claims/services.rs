@@ -0,0 +1,61 @@
+use super::{json_claim, Claim, ClaimError, ClaimExtractor};
+use crate::verify::VerifiedProof;
+
+/// A service with a JSON API, whose claims are values at fixed paths of the response
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonService {
+    pub name: &'static str,
+    /// Server name of the API, case-insensitive
+    pub server_name: &'static str,
+    /// Start of the paths of the requests whose responses have the fields
+    pub path_prefix: &'static str,
+    /// Labels and JSON paths of the claims
+    pub fields: &'static [(&'static str, &'static str)],
+}
+
+impl ClaimExtractor for JsonService {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn matches(&self, server_name: &str, path: Option<&str>) -> bool {
+        server_name.eq_ignore_ascii_case(self.server_name)
+            && path.is_some_and(|path| path.starts_with(self.path_prefix))
+    }
+
+    fn extract(&self, proof: &VerifiedProof) -> Vec<Result<Claim, ClaimError>> {
+        self.fields
+            .iter()
+            .map(|(label, path)| json_claim(proof, label, path))
+            .collect()
+    }
+}
+
+pub(super) const BUILT_IN: &[JsonService] = &[
+    JsonService {
+        name: "GitHub user",
+        server_name: "api.github.com",
+        path_prefix: "/user",
+        fields: &[
+            ("Login", "$.login"),
+            ("User id", "$.id"),
+            ("Name", "$.name"),
+            ("Followers", "$.followers"),
+            ("Public repositories", "$.public_repos"),
+            ("Account created", "$.created_at"),
+        ],
+    },
+    // The API of the TLSNotary examples
+    JsonService {
+        name: "Star Wars character",
+        server_name: "swapi.dev",
+        path_prefix: "/api/people/",
+        fields: &[
+            ("Name", "$.name"),
+            ("Height", "$.height"),
+            ("Mass", "$.mass"),
+            ("Birth year", "$.birth_year"),
+            ("Gender", "$.gender"),
+        ],
+    },
+];
@@ -0,0 +1,302 @@
+use gloo::storage::{LocalStorage, Storage};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{json_claim, range_claim, Claim, ClaimError, ClaimExtractor};
+use crate::policy::{capture_disclosed, header_value, matches_name, parse_path, regex, Message};
+use crate::verify::VerifiedProof;
+
+// Local storage key of the uploaded template files
+const TEMPLATES_KEY: &str = "proof_viz.templates";
+
+/// A template as written in the file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Spec {
+    name: String,
+    server_name: String,
+    #[serde(default)]
+    path: Option<String>,
+    fields: Vec<FieldSpec>,
+}
+
+/// A field as written in the file: a label and one of `json`, `regex` or `header`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldSpec {
+    label: String,
+    #[serde(default)]
+    json: Option<String>,
+    #[serde(default)]
+    regex: Option<String>,
+    #[serde(default)]
+    header: Option<String>,
+}
+
+/// Where the value of a field is in the response
+#[derive(Clone, Debug)]
+enum Field {
+    /// A JSON path into the body, like `$.data.balance`
+    Json(String),
+    /// The first capture group of a regular expression, or the whole match without groups
+    Regex(Regex),
+    /// The value of a header, the name is case-insensitive
+    Header(String),
+}
+
+/// Claims of a service described by the user instead of compiled into the app.
+///
+/// Written as JSON or TOML:
+///
+/// ```toml
+/// name = "Bank balance"
+/// server_name = "*.bank.example"
+/// path = "^/v1/accounts/"
+///
+/// [[fields]]
+/// label = "Balance"
+/// json = "$.balance"
+///
+/// [[fields]]
+/// label = "Account"
+/// regex = "account (\\d+)"
+///
+/// [[fields]]
+/// label = "Date"
+/// header = "Date"
+/// ```
+#[derive(Clone, Debug)]
+pub struct Template {
+    pub name: String,
+    /// Server name, where `*` stands for any part of it
+    pub server_name: String,
+    /// Pattern of the request path
+    pub path: Option<String>,
+    path_regex: Option<Regex>,
+    /// Labels and the fields of the claims
+    fields: Vec<(String, Field)>,
+}
+
+impl Template {
+    /// Parse a template written as JSON or TOML, and check its patterns and paths
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let spec: Spec = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|err| format!("Invalid JSON template: {}", err))?
+        } else {
+            toml::from_str(text).map_err(|err| format!("Invalid TOML template: {}", err))?
+        };
+
+        let path_regex = spec.path.as_deref().map(regex).transpose()?;
+        let fields = spec
+            .fields
+            .into_iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let invalid = |err: String| format!("Field {} ({}): {}", i + 1, field.label, err);
+                let parsed = match (&field.json, &field.regex, &field.header) {
+                    (Some(path), None, None) => {
+                        parse_path(path).map_err(invalid)?;
+                        Field::Json(path.clone())
+                    }
+                    (None, Some(pattern), None) => Field::Regex(regex(pattern).map_err(invalid)?),
+                    (None, None, Some(name)) => Field::Header(name.clone()),
+                    _ => {
+                        return Err(invalid(
+                            "Give exactly one of json, regex and header".to_string(),
+                        ))
+                    }
+                };
+                Ok((field.label, parsed))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            name: spec.name,
+            server_name: spec.server_name,
+            path: spec.path,
+            path_regex,
+            fields,
+        })
+    }
+
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+}
+
+impl ClaimExtractor for Template {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, server_name: &str, path: Option<&str>) -> bool {
+        matches_name(&self.server_name, server_name)
+            && match (&self.path_regex, path) {
+                (None, _) => true,
+                (Some(regex), Some(path)) => regex.is_match(path.as_bytes()),
+                // A redacted path is not known to match
+                (Some(_), None) => false,
+            }
+    }
+
+    fn extract(&self, proof: &VerifiedProof) -> Vec<Result<Claim, ClaimError>> {
+        let recv = &proof.recv;
+        self.fields
+            .iter()
+            .map(|(label, field)| match field {
+                Field::Json(path) => json_claim(proof, label, path),
+                Field::Regex(regex) => capture_disclosed(regex, recv)
+                    .map(|range| range_claim(recv, label, range))
+                    .map_err(|err| ClaimError::new(label, err)),
+                Field::Header(name) => header_value(recv, Message::Response, name)
+                    .map(|range| range_claim(recv, label, range))
+                    .map_err(|err| ClaimError::new(label, err)),
+            })
+            .collect()
+    }
+}
+
+/// A template file the user uploaded
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredTemplate {
+    pub file_name: String,
+    pub text: String,
+}
+
+impl StoredTemplate {
+    pub fn parse(&self) -> Result<Template, String> {
+        Template::parse(&self.text)
+    }
+}
+
+/// The uploaded template files
+pub fn load() -> Vec<StoredTemplate> {
+    LocalStorage::get(TEMPLATES_KEY).unwrap_or_default()
+}
+
+pub fn save(templates: &[StoredTemplate]) {
+    if templates.is_empty() {
+        LocalStorage::delete(TEMPLATES_KEY);
+    } else {
+        let _ = LocalStorage::set(TEMPLATES_KEY, templates);
+    }
+}
+
+/// The templates of the files that are valid. The template settings list the others with the
+/// error of each.
+pub fn parse_all(templates: &[StoredTemplate]) -> Vec<Template> {
+    templates
+        .iter()
+        .filter_map(|template| template.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+name = "Bank balance"
+server_name = "*.bank.example"
+path = "^/v1/accounts/"
+
+[[fields]]
+label = "Balance"
+json = "$.balance"
+
+[[fields]]
+label = "Account"
+regex = "account (\\d+)"
+
+[[fields]]
+label = "Date"
+header = "Date"
+"#;
+
+    #[test]
+    fn toml_template() {
+        let template = Template::parse(TOML).unwrap();
+        assert_eq!(template.name, "Bank balance");
+        assert_eq!(template.server_name, "*.bank.example");
+        assert_eq!(template.path.as_deref(), Some("^/v1/accounts/"));
+        assert_eq!(template.field_count(), 3);
+        assert!(matches!(template.fields[1].1, Field::Regex(_)));
+    }
+
+    #[test]
+    fn json_template() {
+        let template = Template::parse(
+            r#"  {"name": "Bank", "server_name": "api.bank.example", "fields": [{"label": "Balance", "json": "$.balance"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(template.path, None);
+        assert!(matches!(&template.fields[0].1, Field::Json(path) if path == "$.balance"));
+    }
+
+    #[test]
+    fn format_errors() {
+        let err = Template::parse(r#"{"name": "Bank""#).unwrap_err();
+        assert!(err.starts_with("Invalid JSON template"), "{}", err);
+        let err = Template::parse("name = ").unwrap_err();
+        assert!(err.starts_with("Invalid TOML template"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let err = Template::parse(&format!("host = \"a\"\n{}", TOML)).unwrap_err();
+        assert!(err.contains("unknown field `host`"), "{}", err);
+
+        let err =
+            Template::parse(&TOML.replace("header = \"Date\"", "heder = \"Date\"")).unwrap_err();
+        assert!(err.contains("unknown field `heder`"), "{}", err);
+    }
+
+    #[test]
+    fn one_source_per_field() {
+        let both = TOML.replace("header = \"Date\"", "header = \"Date\"\njson = \"$.date\"");
+        assert_eq!(
+            Template::parse(&both).unwrap_err(),
+            "Field 3 (Date): Give exactly one of json, regex and header"
+        );
+        let none = TOML.replace("header = \"Date\"", "");
+        assert_eq!(
+            Template::parse(&none).unwrap_err(),
+            "Field 3 (Date): Give exactly one of json, regex and header"
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_and_paths() {
+        let err = Template::parse(&TOML.replace("account (\\\\d+)", "account (")).unwrap_err();
+        assert!(
+            err.starts_with("Field 2 (Account): Invalid pattern"),
+            "{}",
+            err
+        );
+
+        assert_eq!(
+            Template::parse(&TOML.replace("$.balance", "balance")).unwrap_err(),
+            "Field 1 (Balance): Invalid JSON path balance"
+        );
+
+        let err = Template::parse(&TOML.replace("^/v1/accounts/", "^/v1/(")).unwrap_err();
+        assert!(err.starts_with("Invalid pattern"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_files_are_not_applied() {
+        let templates = [
+            StoredTemplate {
+                file_name: "bank.toml".to_string(),
+                text: TOML.to_string(),
+            },
+            StoredTemplate {
+                file_name: "broken.toml".to_string(),
+                text: "name = ".to_string(),
+            },
+        ];
+        let parsed = parse_all(&templates);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "Bank balance");
+    }
+}
@@ -0,0 +1,244 @@
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Duration;
+
+use similar::{capture_diff_slices_deadline, Algorithm, DiffOp, Instant};
+
+use crate::verify::{ProofReport, Transcript};
+
+/// Transcripts longer than this are compared position by position, aligning them could take
+/// too long when they differ a lot
+const MAX_ALIGN_LEN: usize = 64 * 1024;
+/// How long aligning may take, after that the diff is coarser but still correct
+const ALIGN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A loaded proof that can be selected for a comparison or a time series
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// Id of the proof card
+    pub id: usize,
+    pub name: String,
+    pub report: Rc<ProofReport>,
+}
+
+/// How a transcript byte compares to the other proof
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteDiff {
+    Same,
+    /// Disclosed in both proofs with different contents, or only present in this proof
+    Changed,
+    /// Redacted in this proof, disclosed in the other
+    RedactedHere,
+    /// Disclosed in this proof, redacted in the other
+    RedactedThere,
+    RedactedBoth,
+}
+
+/// The bytes of one transcript, compared to the same transcript of another proof
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TranscriptDiff {
+    /// One entry per transcript byte
+    pub bytes: Vec<ByteDiff>,
+}
+
+impl TranscriptDiff {
+    pub fn count(&self, kind: ByteDiff) -> usize {
+        self.bytes.iter().filter(|diff| **diff == kind).count()
+    }
+}
+
+fn is_redacted(transcript: &Transcript) -> Vec<bool> {
+    let len = transcript.bytes.len();
+    let mut redacted = vec![false; len];
+    for range in &transcript.redacted {
+        for flag in &mut redacted[range.start.min(len)..range.end.min(len)] {
+            *flag = true;
+        }
+    }
+    redacted
+}
+
+/// Compare two transcripts byte by byte, after aligning them so inserted or removed bytes do
+/// not make everything after them differ.
+///
+/// Redacted bytes only match redacted bytes, so a part that is redacted in one proof only is
+/// reported as such instead of as a change.
+pub fn diff_transcripts(left: &Transcript, right: &Transcript) -> (TranscriptDiff, TranscriptDiff) {
+    let tokens = |transcript: &Transcript| -> Vec<(u8, bool)> {
+        transcript
+            .bytes
+            .iter()
+            .copied()
+            .zip(is_redacted(transcript))
+            .map(|(byte, redacted)| if redacted { (0, true) } else { (byte, false) })
+            .collect()
+    };
+    let (old, new) = (tokens(left), tokens(right));
+
+    let mut left_diff = vec![ByteDiff::Changed; old.len()];
+    let mut right_diff = vec![ByteDiff::Changed; new.len()];
+    // Only present in one proof: changed, unless it is redacted there
+    let unpaired = |tokens: &[(u8, bool)], diff: &mut [ByteDiff], range: Range<usize>| {
+        for i in range {
+            diff[i] = if tokens[i].1 {
+                ByteDiff::RedactedHere
+            } else {
+                ByteDiff::Changed
+            };
+        }
+    };
+
+    let ops = if old.len().max(new.len()) > MAX_ALIGN_LEN {
+        let len = old.len().min(new.len());
+        vec![
+            DiffOp::Replace {
+                old_index: 0,
+                old_len: len,
+                new_index: 0,
+                new_len: len,
+            },
+            DiffOp::Delete {
+                old_index: len,
+                old_len: old.len() - len,
+                new_index: len,
+            },
+            DiffOp::Insert {
+                old_index: old.len(),
+                new_index: len,
+                new_len: new.len() - len,
+            },
+        ]
+    } else {
+        let deadline = Instant::now() + ALIGN_TIMEOUT;
+        capture_diff_slices_deadline(Algorithm::Myers, &old, &new, Some(deadline))
+    };
+
+    for op in ops {
+        let (old_range, new_range) = (op.old_range(), op.new_range());
+        if let DiffOp::Equal { .. } = op {
+            for (i, j) in old_range.zip(new_range) {
+                let same = if old[i].1 {
+                    ByteDiff::RedactedBoth
+                } else {
+                    ByteDiff::Same
+                };
+                left_diff[i] = same;
+                right_diff[j] = same;
+            }
+            continue;
+        }
+
+        let paired = old_range.len().min(new_range.len());
+        for k in 0..paired {
+            let (i, j) = (old_range.start + k, new_range.start + k);
+            let (left, right) = match (old[i].1, new[j].1) {
+                // Position by position, equal bytes are not aligned by the diff
+                (false, false) if old[i] == new[j] => (ByteDiff::Same, ByteDiff::Same),
+                (true, true) => (ByteDiff::RedactedBoth, ByteDiff::RedactedBoth),
+                (true, false) => (ByteDiff::RedactedHere, ByteDiff::RedactedThere),
+                (false, true) => (ByteDiff::RedactedThere, ByteDiff::RedactedHere),
+                (false, false) => (ByteDiff::Changed, ByteDiff::Changed),
+            };
+            left_diff[i] = left;
+            right_diff[j] = right;
+        }
+        unpaired(
+            &old,
+            &mut left_diff,
+            old_range.start + paired..old_range.end,
+        );
+        unpaired(
+            &new,
+            &mut right_diff,
+            new_range.start + paired..new_range.end,
+        );
+    }
+
+    (
+        TranscriptDiff { bytes: left_diff },
+        TranscriptDiff { bytes: right_diff },
+    )
+}
+
+#[cfg(test)]
+// Redacted ranges are written as arrays of one range
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use ByteDiff::*;
+
+    fn diff(left: (&str, &[&str]), right: (&str, &[&str])) -> (Vec<ByteDiff>, Vec<ByteDiff>) {
+        let (left, right) = diff_transcripts(
+            &Transcript::redacting(left.0, left.1),
+            &Transcript::redacting(right.0, right.1),
+        );
+        (left.bytes, right.bytes)
+    }
+
+    #[test]
+    fn insertion_only_changes_the_inserted_bytes() {
+        let (left, right) = diff(("GET /a HTTP/1.1", &[]), ("GET /xyz/a HTTP/1.1", &[]));
+        assert_eq!(left, vec![Same; 15]);
+        assert_eq!(right[..5], [Same; 5]);
+        assert_eq!(right[5..9], [Changed; 4]);
+        assert_eq!(right[9..], [Same; 10]);
+    }
+
+    #[test]
+    fn redacted_in_one_proof_only() {
+        let left = ("user=alice&id=1", &["alice"][..]);
+        let right = ("user=bobby&id=1", &[][..]);
+        let (here, there) = diff(left, right);
+        assert_eq!(here[5..10], [RedactedHere; 5]);
+        assert_eq!(there[5..10], [RedactedThere; 5]);
+        assert_eq!(here[..5], [Same; 5]);
+        assert_eq!(here[10..], [Same; 5]);
+
+        // The same comparison the other way around
+        let (there_swapped, here_swapped) = diff(right, left);
+        assert_eq!((here_swapped, there_swapped), (here, there));
+    }
+
+    #[test]
+    fn redacted_in_both_proofs() {
+        let (left, right) = diff(("token=abc; id=1", &["abc"]), ("token=xyz; id=2", &["xyz"]));
+        assert_eq!(left[6..9], [RedactedBoth; 3]);
+        assert_eq!(right[6..9], [RedactedBoth; 3]);
+        assert_eq!(left[14], Changed);
+        assert_eq!(right[14], Changed);
+    }
+
+    #[test]
+    fn redacted_bytes_of_different_length() {
+        let (left, right) = diff(("a=12345;b", &["12345"]), ("a=1;b", &[]));
+        assert_eq!(left.iter().filter(|diff| **diff == RedactedHere).count(), 5);
+        assert_eq!(right[2], RedactedThere);
+        assert_eq!(left[7..], [Same; 2]);
+        assert_eq!(right[3..], [Same; 2]);
+    }
+
+    #[test]
+    fn long_transcripts_compared_by_position() {
+        let len = MAX_ALIGN_LEN + 10;
+        let left = Transcript {
+            bytes: vec![b'a'; len],
+            redacted: vec![],
+        };
+        // One byte inserted at the start, and the last two bytes redacted
+        let mut bytes = vec![b'a'; len + 3];
+        bytes[0] = b'b';
+        let right = Transcript {
+            bytes,
+            redacted: vec![len + 1..len + 3],
+        };
+
+        let (left, right) = diff_transcripts(&left, &right);
+        assert_eq!(left.bytes.len(), len);
+        assert_eq!(right.bytes.len(), len + 3);
+        // Not aligned: the first byte is changed instead of inserted
+        assert_eq!(left.bytes[0], Changed);
+        assert_eq!(left.count(Same), len - 1);
+        assert_eq!(right.bytes[len], Changed);
+        assert_eq!(right.bytes[len + 1..], [RedactedHere; 2]);
+    }
+}
@@ -0,0 +1,74 @@
+use yew::prelude::*;
+
+use crate::claims::{Claim, ClaimError};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Name of the service of the proof
+    pub service: String,
+    pub claims: Vec<Result<Claim, ClaimError>>,
+    /// Index of the claim whose bytes are highlighted
+    pub selected: Option<usize>,
+    /// Called with the index of a claim to highlight its bytes, or `None` to stop
+    pub on_select: Callback<Option<usize>>,
+}
+
+/// What a proof of a known service says, each claim linked to the bytes that support it
+#[function_component(ClaimsComponent)]
+pub fn claims_component(
+    Props {
+        service,
+        claims,
+        selected,
+        on_select,
+    }: &Props,
+) -> Html {
+    let rows = claims.iter().enumerate().map(|(index, claim)| {
+        let claim = match claim {
+            Ok(claim) => claim,
+            Err(err) => {
+                return html! {
+                    <tr class="border-t border-gray-600">
+                        <td class="px-2 py-1">{err.label.clone()}</td>
+                        <td class="px-2 py-1 text-red-400" colspan="2">{"⚠️ "}{err.reason.clone()}</td>
+                    </tr>
+                }
+            }
+        };
+        let is_selected = *selected == Some(index);
+        let onclick = on_select.reform(move |_: MouseEvent| (!is_selected).then_some(index));
+        let location = claim
+            .source
+            .ranges
+            .iter()
+            .map(|range| format!("{}..{}", range.start, range.end))
+            .collect::<Vec<_>>()
+            .join(", ");
+        html! {
+            <tr
+                class={classes!("border-t", "border-gray-600", "cursor-pointer", "hover:bg-zinc-600", is_selected.then_some("bg-green-900"))}
+                title="Show the bytes that support this claim"
+                {onclick}
+            >
+                <td class="px-2 py-1">{claim.label.clone()}</td>
+                <td class="px-2 py-1 font-mono break-all">{claim.value.clone()}</td>
+                <td class="px-2 py-1 text-right text-gray-400 font-mono">
+                    {format!("{} bytes {}", claim.source.direction, location)}
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="p-4 w-5/6">
+            <b>{format!("Claims ({}):", service)}</b>
+            <div class="bg-black text-white p-4 rounded-md">
+                <table class="w-full text-sm">
+                    <tbody>
+                        {for rows}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
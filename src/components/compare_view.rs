@@ -0,0 +1,303 @@
+use web_sys::{Element, HtmlSelectElement};
+use web_time::Duration;
+use yew::prelude::*;
+
+use crate::charset::TranscriptDecoder;
+use crate::compare::{diff_transcripts, ByteDiff, Candidate, TranscriptDiff};
+use crate::redaction::{marked_chars, RedactionStyle};
+use crate::verify::{Transcript, VerifiedProof};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub candidates: Vec<Candidate>,
+    pub redaction: RedactionStyle,
+}
+
+fn diff_class(diff: ByteDiff) -> &'static str {
+    match diff {
+        ByteDiff::Same => "",
+        ByteDiff::Changed => "bg-yellow-700",
+        ByteDiff::RedactedHere => "text-red-400 bg-red-900",
+        ByteDiff::RedactedThere => "bg-blue-800",
+        ByteDiff::RedactedBoth => "text-red-500",
+    }
+}
+
+const LEGEND: [(ByteDiff, &str); 4] = [
+    (ByteDiff::Changed, "changed"),
+    (ByteDiff::RedactedHere, "redacted in this proof only"),
+    (ByteDiff::RedactedThere, "redacted in the other proof only"),
+    (ByteDiff::RedactedBoth, "redacted in both"),
+];
+
+/// The transcript with every run of bytes marked as how it compares to the other proof
+fn view_diff(transcript: &Transcript, diff: &TranscriptDiff, style: &RedactionStyle) -> Html {
+    let decoder = TranscriptDecoder::new(&transcript.bytes);
+    let chars = marked_chars(
+        &transcript.bytes,
+        &decoder,
+        0..transcript.bytes.len(),
+        &transcript.redacted,
+        &RedactionStyle {
+            collapse: false,
+            ..*style
+        },
+    );
+
+    let mut runs: Vec<(ByteDiff, String)> = Vec::new();
+    for marked in chars {
+        let kind = marked
+            .offset
+            .and_then(|offset| diff.bytes.get(offset).copied())
+            .unwrap_or(ByteDiff::Same);
+        match runs.last_mut() {
+            Some((last, text)) if *last == kind => text.push(marked.c),
+            _ => runs.push((kind, marked.c.to_string())),
+        }
+    }
+
+    html! {
+        <pre class="bg-black text-white p-4 rounded-md text-xs whitespace-pre-wrap break-all">
+            {for runs.into_iter().map(|(kind, text)| match kind {
+                ByteDiff::Same => html! { {text} },
+                kind => html! { <span class={diff_class(kind)}>{text}</span> },
+            })}
+        </pre>
+    }
+}
+
+/// The diffs of the sent and received transcripts of two proofs
+struct Diffs {
+    sent: (TranscriptDiff, TranscriptDiff),
+    recv: (TranscriptDiff, TranscriptDiff),
+}
+
+fn view_transcripts(
+    title: &str,
+    left: &Transcript,
+    right: &Transcript,
+    (left_diff, right_diff): &(TranscriptDiff, TranscriptDiff),
+    style: &RedactionStyle,
+) -> Html {
+    let summary = |diff: &TranscriptDiff| {
+        format!(
+            "{} bytes changed, {} redacted in this proof only",
+            diff.count(ByteDiff::Changed),
+            diff.count(ByteDiff::RedactedHere)
+        )
+    };
+
+    html! {
+        <div class="mt-4">
+            <b>{title}</b>
+            <div class="grid grid-cols-2 gap-2">
+                <div>
+                    <p class="text-sm text-gray-400">{summary(left_diff)}</p>
+                    {view_diff(left, left_diff, style)}
+                </div>
+                <div>
+                    <p class="text-sm text-gray-400">{summary(right_diff)}</p>
+                    {view_diff(right, right_diff, style)}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Rows of the side-by-side table: label and the value of each proof
+fn fields(
+    left: &VerifiedProof,
+    right: &VerifiedProof,
+    left_notary: &str,
+    right_notary: &str,
+) -> Vec<(String, String, String)> {
+    let time = |time: u64| {
+        (chrono::DateTime::UNIX_EPOCH + Duration::from_secs(time))
+            .format("%Y-%m-%d %H:%M:%S UTC")
+            .to_string()
+    };
+    let mut fields = vec![
+        (
+            "Server domain".to_string(),
+            left.server_name.clone(),
+            right.server_name.clone(),
+        ),
+        (
+            "Notarization time".to_string(),
+            time(left.time),
+            time(right.time),
+        ),
+        (
+            "Notary".to_string(),
+            left_notary.to_string(),
+            right_notary.to_string(),
+        ),
+    ];
+
+    let chain_len = left.certificates.len().max(right.certificates.len());
+    for i in 0..chain_len {
+        let (a, b) = (left.certificates.get(i), right.certificates.get(i));
+        let label = |field: &str| {
+            if i == 0 {
+                format!("Server certificate {}", field)
+            } else {
+                format!("CA certificate {} {}", i, field)
+            }
+        };
+        let value = |certificate: Option<_>,
+                     f: fn(&crate::certificate::CertificateInfo) -> String| {
+            certificate.map(f).unwrap_or_default()
+        };
+        fields.push((
+            label("subject"),
+            value(a, |c| c.subject.clone()),
+            value(b, |c| c.subject.clone()),
+        ));
+        fields.push((
+            label("issuer"),
+            value(a, |c| c.issuer.clone()),
+            value(b, |c| c.issuer.clone()),
+        ));
+        fields.push((
+            label("validity"),
+            value(a, |c| format!("{} – {}", c.not_before, c.not_after)),
+            value(b, |c| format!("{} – {}", c.not_before, c.not_after)),
+        ));
+        fields.push((
+            label("SHA-256"),
+            value(a, |c| c.fingerprint.clone()),
+            value(b, |c| c.fingerprint.clone()),
+        ));
+    }
+    fields
+}
+
+/// Compare two verified proofs side by side, e.g. two notarizations of the same endpoint
+#[function_component(CompareComponent)]
+pub fn compare_component(
+    Props {
+        candidates,
+        redaction,
+    }: &Props,
+) -> Html {
+    let selected = use_state(|| {
+        (
+            candidates.first().map(|candidate| candidate.id),
+            candidates.get(1).map(|candidate| candidate.id),
+        )
+    });
+
+    let open = use_state(|| false);
+
+    let find = |id: Option<usize>| candidates.iter().find(|candidate| Some(candidate.id) == id);
+    let (left, right) = (find(selected.0), find(selected.1));
+
+    // Aligning the transcripts is slow, so it is only done while the comparison is open and
+    // once per pair of proofs
+    let pair = left
+        .zip(right)
+        .map(|(left, right)| (left.report.clone(), right.report.clone()));
+    let diffs = use_memo((pair, *open), |(pair, open)| {
+        let (left, right) = pair.as_ref().filter(|_| *open)?;
+        let (Ok(left), Ok(right)) = (&left.result, &right.result) else {
+            return None;
+        };
+        Some(Diffs {
+            sent: diff_transcripts(&left.sent, &right.sent),
+            recv: diff_transcripts(&left.recv, &right.recv),
+        })
+    });
+    let ontoggle = {
+        let open = open.clone();
+        Callback::from(move |e: Event| {
+            let details: Element = e.target_unchecked_into();
+            open.set(details.has_attribute("open"));
+        })
+    };
+
+    let select = |side: usize| {
+        let selected = selected.clone();
+        let current = if side == 0 { selected.0 } else { selected.1 };
+        let onchange = {
+            let selected = selected.clone();
+            Callback::from(move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                let id = select.value().parse().ok();
+                selected.set(if side == 0 {
+                    (id, selected.1)
+                } else {
+                    (selected.0, id)
+                });
+            })
+        };
+        html! {
+            <select class="p-2 bg-zinc-700 border border-gray-600 rounded" {onchange}>
+                <option value="" selected={current.is_none()}>{"Select a proof"}</option>
+                {for candidates.iter().map(|candidate| html! {
+                    <option value={candidate.id.to_string()} selected={current == Some(candidate.id)}>{candidate.name.clone()}</option>
+                })}
+            </select>
+        }
+    };
+
+    let comparison = match (left, right) {
+        (Some(left), Some(right)) => match (&left.report.result, &right.report.result) {
+            (Ok(left_proof), Ok(right_proof)) => {
+                let rows = fields(
+                    left_proof,
+                    right_proof,
+                    &left.report.notary,
+                    &right.report.notary,
+                );
+                html! {
+                    <>
+                        <table class="w-full text-sm mt-2 table-fixed">
+                            <tbody>
+                                {for rows.into_iter().map(|(label, a, b)| {
+                                    let class = if a == b { "" } else { "text-yellow-400" };
+                                    html! {
+                                        <tr class="border-t border-gray-600">
+                                            <td class="px-2 py-1 w-48">{label}</td>
+                                            <td class={classes!("px-2", "py-1", "font-mono", "break-all", class)}>{a}</td>
+                                            <td class={classes!("px-2", "py-1", "font-mono", "break-all", class)}>{b}</td>
+                                        </tr>
+                                    }
+                                })}
+                            </tbody>
+                        </table>
+                        <div class="flex flex-wrap gap-4 text-sm mt-4">
+                            {for LEGEND.iter().map(|(kind, label)| html! {
+                                <span class={diff_class(*kind)}>{label}</span>
+                            })}
+                        </div>
+                        if let Some(diffs) = diffs.as_ref() {
+                            {view_transcripts("Sent", &left_proof.sent, &right_proof.sent, &diffs.sent, redaction)}
+                            {view_transcripts("Received", &left_proof.recv, &right_proof.recv, &diffs.recv, redaction)}
+                        }
+                    </>
+                }
+            }
+            _ => html! {},
+        },
+        _ => html! {
+            <p class="text-sm text-gray-400 mt-2">{"Select two proofs to compare"}</p>
+        },
+    };
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={*open} {ontoggle}>
+                    <summary class="cursor-pointer px-8 py-2"><b>{"Compare two proofs"}</b></summary>
+                    <div class="px-8">
+                        <div class="grid grid-cols-2 gap-2">
+                            {select(0)}
+                            {select(1)}
+                        </div>
+                        {comparison}
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
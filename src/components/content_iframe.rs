@@ -1,80 +1,282 @@
 use gloo::console::log;
 use std::fmt;
+use std::rc::Rc;
 
 use spansy::http::parse_response;
-use wasm_bindgen::prelude::*;
 use yew::prelude::*;
 
+use crate::charset::TranscriptDecoder;
+use crate::highlight::{highlight, Highlights, Language};
+use crate::redaction::{format_count, marked_chars, runs, MarkedChar, RedactionStyle, Run};
+use crate::report::escape_html;
+use crate::verify::ProofReport;
+
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
-    pub bytes: Vec<u8>,
+    /// The verified proof whose response is shown
+    pub report: Rc<ProofReport>,
+    pub redaction: RedactionStyle,
+    #[prop_or_default]
+    pub highlights: Highlights,
 }
 
-fn render_json(content: String) -> String {
-    let json = serde_json::from_str::<serde_json::Value>(content.as_str());
-    if let Ok(json) = json {
-        serde_json::to_string_pretty(&json).unwrap()
-    } else {
-        content
+// Marks redacted text inside the rendered HTML preview
+const REDACTED_STYLE: &str = "<style>mark.tlsn-redacted { color: red; background: #fee2e2; outline: 1px dashed red; }</style>";
+const REDACTED_BOX_STYLE: &str = "<style>mark.tlsn-redacted { color: #b91c1c; background: #fecaca; border: 1px solid red; border-radius: 2px; }</style>";
+
+/// Re-indent JSON character by character so every character keeps its redaction flag.
+///
+/// Redacted characters are copied verbatim and never change the parser state: a redacted
+/// value usually makes the JSON invalid, so it can not be parsed and pretty printed as a whole.
+fn render_json(chars: &[MarkedChar]) -> Vec<MarkedChar> {
+    fn newline(out: &mut Vec<MarkedChar>, indent: usize) {
+        out.push(MarkedChar::inserted('\n'));
+        out.extend(std::iter::repeat(MarkedChar::inserted(' ')).take(2 * indent));
+    }
+
+    let mut out = Vec::with_capacity(chars.len());
+    let mut indent = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    let mut iter = chars.iter().copied().peekable();
+    while let Some(marked) = iter.next() {
+        let MarkedChar { c, redacted, .. } = marked;
+        if redacted {
+            out.push(marked);
+            continue;
+        }
+
+        if in_string {
+            out.push(marked);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(marked);
+            }
+            '{' | '[' => {
+                out.push(marked);
+                // Keep empty objects and arrays on one line
+                while iter
+                    .next_if(|next| !next.redacted && next.c.is_whitespace())
+                    .is_some()
+                {}
+                match iter.peek() {
+                    Some(next) if !next.redacted && (next.c == '}' || next.c == ']') => {}
+                    _ => {
+                        indent += 1;
+                        newline(&mut out, indent);
+                    }
+                }
+            }
+            '}' | ']' => {
+                let after_open = matches!(
+                    out.last(),
+                    Some(MarkedChar {
+                        c: '{' | '[',
+                        redacted: false,
+                        ..
+                    })
+                );
+                if !after_open {
+                    indent = indent.saturating_sub(1);
+                    newline(&mut out, indent);
+                }
+                out.push(marked);
+            }
+            ',' => {
+                out.push(marked);
+                newline(&mut out, indent);
+            }
+            ':' => {
+                out.push(marked);
+                out.push(MarkedChar::inserted(' '));
+            }
+            c if c.is_whitespace() => {}
+            _ => out.push(marked),
+        }
     }
-}
 
-#[derive(Debug)]
-enum ContentType {
-    Html,
-    Json,
-    Other,
+    out
 }
-fn get_content_type(bytes: &[u8]) -> (ContentType, String) {
-    match parse_response(&bytes) {
-        Ok(x) => {
-            // log!(format!("Test {:?}", x.headers));
 
-            let content_type = (&x)
-                .header("Content-Type")
-                .map_or(ContentType::Other, |header| {
-                    let type_string = String::from_utf8_lossy(header.value.as_bytes());
-                    match type_string {
-                        s if s.contains("text/html") => ContentType::Html,
-                        s if s.contains("application/json") => ContentType::Json,
-                        _ => ContentType::Other,
+/// Build the `srcdoc` of the HTML preview, wrapping redacted text in a `<mark>`.
+///
+/// Redactions inside tags, comments, scripts and style sheets are left as they are, wrapping
+/// them would break the markup.
+fn render_html(chars: &[MarkedChar], style: &RedactionStyle) -> String {
+    #[derive(PartialEq)]
+    enum State {
+        Text,
+        Tag,
+        Comment,
+        RawText(&'static str),
+    }
+
+    fn ends_with_ignore_case(html: &str, suffix: &str) -> bool {
+        let start = html.len().saturating_sub(suffix.len());
+        html.is_char_boundary(start) && html[start..].eq_ignore_ascii_case(suffix)
+    }
+
+    let mut html = String::from(if style.boxes {
+        REDACTED_BOX_STYLE
+    } else {
+        REDACTED_STYLE
+    });
+    let mut state = State::Text;
+    // Lowercase name of the current tag, prefixed with `/` for closing tags
+    let mut tag_name = String::new();
+    let mut tag_name_done = false;
+
+    for Run {
+        text,
+        redacted,
+        byte_count,
+    } in runs(chars)
+    {
+        if redacted && state == State::Text {
+            html.push_str(&format!(
+                "<mark class=\"tlsn-redacted\" title=\"{} redacted bytes\">{}</mark>",
+                format_count(byte_count),
+                // The glyph is chosen by the user and may be markup
+                escape_html(&text)
+            ));
+            continue;
+        }
+
+        for c in text.chars() {
+            html.push(c);
+            match state {
+                State::Text if c == '<' => {
+                    state = State::Tag;
+                    tag_name.clear();
+                    tag_name_done = false;
+                }
+                State::Tag if html.ends_with("<!--") => state = State::Comment,
+                State::Tag if c == '>' => {
+                    state = match tag_name.as_str() {
+                        "script" => State::RawText("</script"),
+                        "style" => State::RawText("</style"),
+                        _ => State::Text,
+                    };
+                }
+                State::Tag if !tag_name_done => {
+                    if c.is_ascii_alphanumeric() || (c == '/' && tag_name.is_empty()) {
+                        tag_name.push(c.to_ascii_lowercase());
+                    } else {
+                        tag_name_done = true;
                     }
-                });
+                }
+                State::Comment if html.ends_with("-->") => state = State::Text,
+                State::RawText(end) if ends_with_ignore_case(&html, end) => {
+                    state = State::Tag;
+                    tag_name = end[1..].to_string();
+                    tag_name_done = true;
+                }
+                _ => {}
+            }
+        }
+    }
 
-            let body = x.body.map_or(String::new(), |body| {
-                String::from_utf8_lossy(body.as_bytes()).to_string()
-            });
+    html
+}
 
-            // log!(format!("Test {:?}", content_type));
+fn get_content_type(bytes: &[u8]) -> Language {
+    match parse_response(&bytes) {
+        Ok(x) => {
+            // log!(format!("Test {:?}", x.headers));
 
-            (content_type, body)
+            (&x).header("Content-Type")
+                .map_or(Language::Plain, |header| {
+                    Language::from_content_type(&String::from_utf8_lossy(header.value.as_bytes()))
+                })
         }
-        Err(e) => (ContentType::Other, e.to_string()),
+        Err(_) => Language::Plain,
     }
 }
 
 #[function_component]
 pub fn ContentIFrame(props: &Props) -> Html {
-    // JavaScript function to trigger Prism highlighting
-    use_effect(move || highlight_code());
+    let Props {
+        report,
+        redaction,
+        highlights,
+    } = props;
 
-    match get_content_type(&props.bytes) {
-        (ContentType::Html, content_html) => html! {
+    // Decoded once per proof and style, highlights change with every search
+    let content = use_memo((report.clone(), *redaction), |(report, redaction)| {
+        let Ok(proof) = &report.result else {
+            return (Language::Plain, Vec::new());
+        };
+        let bytes = &proof.recv.bytes;
+        // Decode the body with its own charset rather than assuming UTF-8
+        let decoder = TranscriptDecoder::new(bytes);
+        let body = marked_chars(
+            bytes,
+            &decoder,
+            decoder.body_start()..bytes.len(),
+            &proof.recv.redacted,
+            redaction,
+        );
+        let language = get_content_type(bytes);
+        let body = match body.iter().find(|c| !c.c.is_whitespace()) {
+            Some(MarkedChar {
+                c: '{' | '[',
+                redacted: false,
+                ..
+            }) if language == Language::Json => render_json(&body),
+            _ => body,
+        };
+        (language, body)
+    });
+    let (language, body) = &*content;
+
+    match language {
+        Language::Html => html! {
+            <>
+                <details class="p-4 w-5/6" open={true}>
+                    <summary><b>{"Received HTML content:"}</b></summary>
+                    // The HTML comes from the server: without scripts and in an origin of its own,
+                    // it can not reach the proof history, policies or templates of the app
+                    <iframe class="w-full h-64" sandbox="" srcdoc={render_html(body, redaction)} src="demo_iframe_srcdoc.htm">
+                        <p>{">Your browser does not support iframes."}</p>
+                    </iframe>
+                </details>
+                <details class="p-4 w-5/6" open={false}>
+                    <summary><b>{"Received HTML source:"}</b></summary>
+                    <div class="bg-black text-white p-4 rounded-md overflow-x-auto">
+                        <pre>
+                            <code>{highlight(body, Language::Html, highlights, redaction)}</code>
+                        </pre>
+                    </div>
+                </details>
+            </>
+        },
+        Language::Json => html! {
             <details class="p-4 w-5/6" open={true}>
-                <summary><b>{"Received HTML content:"}</b></summary>
-                <iframe class="w-full h-64" srcdoc={content_html} src="demo_iframe_srcdoc.htm">
-                    <p>{">Your browser does not support iframes."}</p>
-                </iframe>
+                <summary><b>{"Received JSON content:"}</b></summary>
+                <div class="bg-black text-white p-4 rounded-md overflow-x-auto">
+                    <pre>
+                        <code>{highlight(body, Language::Json, highlights, redaction)}</code>
+                    </pre>
+                </div>
             </details>
         },
-        (ContentType::Json, content_json) => html! {
+        Language::Xml => html! {
             <details class="p-4 w-5/6" open={true}>
-                <summary><b>{"Received JSON content:"}</b></summary>
+                <summary><b>{"Received XML content:"}</b></summary>
                 <div class="bg-black text-white p-4 rounded-md overflow-x-auto">
                     <pre>
-                        <code class="lang-json">
-                            {render_json(content_json)}
-                        </code>
+                        <code>{highlight(body, Language::Xml, highlights, redaction)}</code>
                     </pre>
                 </div>
             </details>
@@ -82,8 +284,3 @@ pub fn ContentIFrame(props: &Props) -> Html {
         _ => html! {},
     }
 }
-
-#[wasm_bindgen(inline_js = "export function highlight_code() { Prism.highlightAll(); }")]
-extern "C" {
-    fn highlight_code();
-}
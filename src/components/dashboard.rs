@@ -0,0 +1,310 @@
+use chrono::NaiveDate;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use web_time::Duration;
+use yew::prelude::*;
+
+use crate::components::view_file::card_id;
+use crate::export::{csv, download};
+use crate::verify::{ProofReport, Status};
+
+/// A row of the dashboard, one per proof file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    /// Id of the proof card
+    pub id: usize,
+    pub name: String,
+    pub proof_hash: String,
+    pub server_name: Option<String>,
+    /// Notarization time, in seconds since the Unix epoch
+    pub time: Option<u64>,
+    pub notary: String,
+    pub status: Status,
+    pub disclosed_percent: Option<f64>,
+}
+
+impl Row {
+    pub fn new(id: usize, name: &str, report: &ProofReport) -> Self {
+        let proof = report.result.as_ref().ok();
+        Self {
+            id,
+            name: name.to_string(),
+            proof_hash: report.proof_hash.clone(),
+            server_name: proof.map(|proof| proof.server_name.clone()),
+            time: proof.map(|proof| proof.time),
+            notary: report.notary.clone(),
+            status: report.status(),
+            disclosed_percent: proof.map(|proof| proof.disclosed_percent()),
+        }
+    }
+
+    fn date_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.time
+            .map(|time| chrono::DateTime::UNIX_EPOCH + Duration::from_secs(time))
+    }
+
+    fn time_string(&self) -> String {
+        self.date_time()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_default()
+    }
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.proof_hash.clone(),
+            self.server_name.clone().unwrap_or_default(),
+            self.time_string(),
+            self.notary.clone(),
+            self.status.to_string(),
+            self.disclosed_percent
+                .map(|percent| format!("{:.1}", percent))
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "file": self.name,
+            "proof_hash": self.proof_hash,
+            "server_name": self.server_name,
+            "notarization_time": self.time,
+            "notary": self.notary,
+            "status": self.status.to_string(),
+            "disclosed_percent": self.disclosed_percent,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Column {
+    Name,
+    ProofHash,
+    Server,
+    Time,
+    Notary,
+    Status,
+    Disclosed,
+}
+
+const COLUMNS: [(Column, &str); 7] = [
+    (Column::Name, "File"),
+    (Column::ProofHash, "Proof hash"),
+    (Column::Server, "Server domain"),
+    (Column::Time, "Notarization time"),
+    (Column::Notary, "Notary"),
+    (Column::Status, "Status"),
+    (Column::Disclosed, "Disclosed"),
+];
+
+fn compare(column: Column, a: &Row, b: &Row) -> std::cmp::Ordering {
+    match column {
+        Column::Name => a.name.cmp(&b.name),
+        Column::ProofHash => a.proof_hash.cmp(&b.proof_hash),
+        Column::Server => a.server_name.cmp(&b.server_name),
+        Column::Time => a.time.cmp(&b.time),
+        Column::Notary => a.notary.cmp(&b.notary),
+        Column::Status => a.status.to_string().cmp(&b.status.to_string()),
+        Column::Disclosed => a
+            .disclosed_percent
+            .partial_cmp(&b.disclosed_percent)
+            .unwrap_or(std::cmp::Ordering::Equal),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Filters {
+    status: Option<Status>,
+    /// Part of the server domain, case-insensitive
+    domain: String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl Filters {
+    fn matches(&self, row: &Row) -> bool {
+        let date = row.date_time().map(|time| time.date_naive());
+        let domain = self.domain.trim().to_lowercase();
+        self.status.map_or(true, |status| row.status == status)
+            && (domain.is_empty()
+                || row
+                    .server_name
+                    .as_ref()
+                    .map_or(false, |name| name.to_lowercase().contains(&domain)))
+            && self
+                .from
+                .map_or(true, |from| date.map_or(false, |date| date >= from))
+            && self
+                .to
+                .map_or(true, |to| date.map_or(false, |date| date <= to))
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub rows: Vec<Row>,
+}
+
+/// Show the card of a proof
+pub(crate) fn scroll_to_card(id: usize) {
+    if let Some(card) = gloo::utils::document().get_element_by_id(&card_id(id)) {
+        card.scroll_into_view();
+    }
+}
+
+const HEADER: [&str; 7] = [
+    "file",
+    "proof_hash",
+    "server_name",
+    "notarization_time",
+    "notary",
+    "status",
+    "disclosed_percent",
+];
+
+#[function_component(DashboardComponent)]
+pub fn dashboard_component(Props { rows }: &Props) -> Html {
+    let sort = use_state(|| (Column::Time, false));
+    let filters = use_state(Filters::default);
+
+    let mut visible: Vec<&Row> = rows.iter().filter(|row| filters.matches(row)).collect();
+    let (sort_column, ascending) = *sort;
+    visible.sort_by(|a, b| {
+        let ordering = compare(sort_column, a, b);
+        if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+
+    let set_filter = |update: fn(&mut Filters, String)| {
+        let filters = filters.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_filters = (*filters).clone();
+            update(&mut new_filters, input.value());
+            filters.set(new_filters);
+        })
+    };
+
+    let onchange_status = {
+        let filters = filters.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let status = Status::ALL
+                .into_iter()
+                .find(|status| status.to_string() == select.value());
+            filters.set(Filters {
+                status,
+                ..(*filters).clone()
+            });
+        })
+    };
+
+    let export_csv = {
+        let rows: Vec<Vec<String>> = visible.iter().map(|row| row.fields()).collect();
+        Callback::from(move |_: MouseEvent| {
+            download("proofs.csv", "text/csv", &csv(&HEADER, &rows));
+        })
+    };
+
+    let export_json = {
+        let json = serde_json::Value::Array(visible.iter().map(|row| row.to_json()).collect());
+        Callback::from(move |_: MouseEvent| {
+            let json = serde_json::to_string_pretty(&json).unwrap_or_default();
+            download("proofs.json", "application/json", &json);
+        })
+    };
+
+    let header = COLUMNS.iter().map(|&(column, label)| {
+        let onclick = {
+            let sort = sort.clone();
+            Callback::from(move |_: MouseEvent| {
+                let (current, ascending) = *sort;
+                sort.set((column, current != column || !ascending));
+            })
+        };
+        let arrow = match *sort {
+            (current, true) if current == column => " ▲",
+            (current, false) if current == column => " ▼",
+            _ => "",
+        };
+        html! {
+            <th class="px-2 py-1 text-left cursor-pointer select-none" {onclick}>{label}{arrow}</th>
+        }
+    });
+
+    let status_class = |status: Status| match status {
+        Status::Verified => "text-green-400",
+        Status::Invalid => "text-red-400",
+        Status::Unreadable => "text-gray-400",
+    };
+
+    let body = visible.iter().map(|row| {
+        let id = row.id;
+        html! {
+            <tr class="border-t border-gray-600 cursor-pointer hover:bg-zinc-600" onclick={Callback::from(move |_: MouseEvent| scroll_to_card(id))}>
+                <td class="px-2 py-1">{row.name.clone()}</td>
+                <td class="px-2 py-1 font-mono" title={row.proof_hash.clone()}>{&row.proof_hash[..row.proof_hash.len().min(16)]}</td>
+                <td class="px-2 py-1">{row.server_name.clone().unwrap_or_default()}</td>
+                <td class="px-2 py-1">{row.time_string()}</td>
+                <td class="px-2 py-1 font-mono" title={row.notary.clone()}>{&row.notary[..row.notary.len().min(16)]}</td>
+                <td class={classes!("px-2", "py-1", status_class(row.status))}>{row.status.to_string()}</td>
+                <td class="px-2 py-1 text-right">
+                    {row.disclosed_percent.map(|percent| format!("{:.1}%", percent)).unwrap_or_default()}
+                </td>
+            </tr>
+        }
+    });
+
+    let input_class = "p-2 bg-zinc-700 border border-gray-600 rounded";
+    let button_class = "px-4 py-2 hover:bg-black hover:text-white rounded border-white border";
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={true}>
+                    <summary class="cursor-pointer px-8 py-2">
+                        <b>{format!("Proofs: {} of {}", visible.len(), rows.len())}</b>
+                    </summary>
+                    <div class="px-8 flex flex-wrap gap-4 items-center text-sm">
+                        <label class="flex gap-2 items-center">
+                            {"Status"}
+                            <select class={input_class} onchange={onchange_status}>
+                                <option value="" selected={filters.status.is_none()}>{"All"}</option>
+                                {for Status::ALL.iter().map(|status| html! {
+                                    <option value={status.to_string()} selected={filters.status == Some(*status)}>{status.to_string()}</option>
+                                })}
+                            </select>
+                        </label>
+                        <label class="flex gap-2 items-center">
+                            {"Domain"}
+                            <input class={input_class} type="text" value={filters.domain.clone()} onchange={set_filter(|filters, value| filters.domain = value)} />
+                        </label>
+                        <label class="flex gap-2 items-center">
+                            {"From"}
+                            <input class={input_class} type="date" onchange={set_filter(|filters, value| filters.from = NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok())} />
+                        </label>
+                        <label class="flex gap-2 items-center">
+                            {"To"}
+                            <input class={input_class} type="date" onchange={set_filter(|filters, value| filters.to = NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok())} />
+                        </label>
+                        <div class="flex-1"></div>
+                        <button class={button_class} onclick={export_csv}>{"Export CSV"}</button>
+                        <button class={button_class} onclick={export_json}>{"Export JSON"}</button>
+                    </div>
+                    <div class="px-8 py-2 overflow-x-auto">
+                        <table class="w-full text-sm">
+                            <thead>
+                                <tr>{for header}</tr>
+                            </thead>
+                            <tbody>
+                                {for body}
+                            </tbody>
+                        </table>
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,140 @@
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+use yew::prelude::*;
+
+use crate::components::redacted_bytes_component::Direction;
+use crate::highlight::redaction_selector;
+use crate::redaction::{format_count, segments, DisclosureStats, Segment};
+use crate::verify::{ProofReport, Transcript};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// The proof card, which contains the `RedactedBytesComponent`s to jump into
+    pub card_ref: NodeRef,
+    pub report: Rc<ProofReport>,
+}
+
+/// Scroll the bytes of `direction` to the segment, and inspect it when it is redacted
+fn jump_to(card_ref: &NodeRef, direction: &Direction, segment: &Segment) {
+    let Some(card) = card_ref.cast::<Element>() else {
+        return;
+    };
+    let query = |selector: String| card.query_selector(&selector).ok().flatten();
+
+    let transcript_selector = format!("[data-direction=\"{}\"]", direction);
+    let Some(transcript) = query(transcript_selector.clone()) else {
+        return;
+    };
+    let _ = transcript.set_attribute("open", "");
+
+    // Disclosed segments start right after a redaction, or at the start of the transcript
+    let target = if segment.redacted {
+        query(format!(
+            "{} {}",
+            transcript_selector,
+            redaction_selector(segment.range.start)
+        ))
+    } else {
+        None
+    };
+
+    match target {
+        Some(target) => {
+            target.scroll_into_view();
+            if let Ok(target) = target.dyn_into::<HtmlElement>() {
+                target.click();
+            }
+        }
+        None if segment.range.start > 0 => {
+            let previous = card
+                .query_selector_all(&format!("{} [data-redaction]", transcript_selector))
+                .ok()
+                .and_then(|redactions| {
+                    (0..redactions.length())
+                        .filter_map(|i| redactions.item(i)?.dyn_into::<Element>().ok())
+                        .filter(|element| {
+                            element
+                                .get_attribute("data-redaction")
+                                .and_then(|start| start.parse::<usize>().ok())
+                                .map_or(false, |start| start < segment.range.start)
+                        })
+                        .last()
+                });
+            previous.unwrap_or(transcript).scroll_into_view();
+        }
+        None => transcript.scroll_into_view(),
+    }
+}
+
+#[function_component(DisclosureStatsComponent)]
+pub fn disclosure_stats_component(props: &Props) -> Html {
+    let Props { card_ref, report } = props;
+    let Ok(proof) = &report.result else {
+        return html! {};
+    };
+
+    let transcript = |direction: Direction| {
+        let Transcript { bytes, redacted } = proof.transcript(&direction);
+        let len = bytes.len();
+        let stats = DisclosureStats::new(len, redacted);
+        let bar = segments(len, redacted).into_iter().map(|segment| {
+            let onclick = {
+                let card_ref = card_ref.clone();
+                let direction = direction.clone();
+                let segment = segment.clone();
+                Callback::from(move |_: MouseEvent| jump_to(&card_ref, &direction, &segment))
+            };
+            let title = format!(
+                "{} bytes {}..{} ({} bytes)",
+                if segment.redacted { "Redacted" } else { "Disclosed" },
+                segment.range.start,
+                segment.range.end,
+                format_count(segment.range.len())
+            );
+            html! {
+                <div
+                    class={classes!("cursor-pointer", "hover:opacity-75", if segment.redacted { "bg-red-600" } else { "bg-green-600" })}
+                    style={format!("flex: {} 0 0; min-width: 2px;", segment.range.len())}
+                    {title}
+                    {onclick}
+                />
+            }
+        });
+
+        html! {
+            <div class="mb-2">
+                <p class="text-sm">
+                    <b class="capitalize">{direction.to_string()}</b>
+                    {format!(
+                        ": {} bytes, {} disclosed ({:.1}%), ",
+                        format_count(stats.total),
+                        format_count(stats.disclosed()),
+                        stats.disclosed_percent()
+                    )}
+                    <span style="color:red;">{format!("{} redacted ({:.1}%)", format_count(stats.redacted), stats.redacted_percent())}</span>
+                    {format!(
+                        " in {} {}, largest {} bytes",
+                        stats.ranges,
+                        if stats.ranges == 1 { "range" } else { "ranges" },
+                        format_count(stats.largest)
+                    )}
+                </p>
+                <div class="flex h-3 w-full rounded overflow-hidden mt-1">
+                    {for bar}
+                </div>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="p-4 w-5/6">
+            <b>{"Disclosure:"}</b>
+            <div class="bg-black text-white p-4 rounded-md">
+                {transcript(Direction::Send)}
+                {transcript(Direction::Received)}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use web_sys::HtmlInputElement;
+use web_time::Duration;
+use yew::prelude::*;
+
+use crate::history::Entry;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub enabled: bool,
+    pub entries: Vec<Entry>,
+    /// Fingerprint of the current Notary key
+    pub notary: String,
+    pub on_toggle: Callback<bool>,
+    /// Called with the hash of the entry to show
+    pub on_open: Callback<String>,
+    /// Called with the hash of the entry to delete
+    pub on_delete: Callback<String>,
+    pub on_clear: Callback<()>,
+}
+
+fn date(time: Option<u64>) -> String {
+    time.map(|time| {
+        (chrono::DateTime::UNIX_EPOCH + Duration::from_secs(time))
+            .format("%Y-%m-%d")
+            .to_string()
+    })
+    .unwrap_or_else(|| "Unknown date".to_string())
+}
+
+#[function_component(HistorySidebar)]
+pub fn history_sidebar(props: &Props) -> Html {
+    let Props {
+        enabled,
+        entries,
+        notary,
+        on_toggle,
+        on_open,
+        on_delete,
+        on_clear,
+    } = props;
+
+    let onchange = {
+        let on_toggle = on_toggle.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_toggle.emit(input.checked());
+        })
+    };
+
+    // Group by domain, then by notarization date, newest first
+    let mut groups: BTreeMap<String, BTreeMap<String, Vec<&Entry>>> = BTreeMap::new();
+    for entry in entries {
+        let domain = entry
+            .server_name
+            .clone()
+            .unwrap_or_else(|| "Unknown domain".to_string());
+        groups
+            .entry(domain)
+            .or_default()
+            .entry(date(entry.time))
+            .or_default()
+            .push(entry);
+    }
+
+    let view_entry = |entry: &Entry| {
+        let status = if entry.notary != *notary {
+            "⏳"
+        } else if entry.is_verified() {
+            "✅"
+        } else {
+            "❌"
+        };
+        let title = if entry.notary != *notary {
+            "Verifying with the current Notary key".to_string()
+        } else {
+            format!("Proof {} with the current Notary key", entry.status)
+        };
+        let onclick_open = on_open.reform({
+            let hash = entry.hash.clone();
+            move |_: MouseEvent| hash.clone()
+        });
+        let onclick_delete = on_delete.reform({
+            let hash = entry.hash.clone();
+            move |_: MouseEvent| hash.clone()
+        });
+        html! {
+            <li class="flex gap-2 items-center">
+                <span {title}>{status}</span>
+                <button class="flex-1 text-left truncate hover:underline" title={entry.name.clone()} onclick={onclick_open}>{entry.name.clone()}</button>
+                <button class="px-1 hover:bg-black rounded" title="Delete from history" onclick={onclick_delete}>{"✕"}</button>
+            </li>
+        }
+    };
+
+    html! {
+        <aside class="w-72 shrink-0 p-4 text-sm">
+            <b>{"History"}</b>
+            <label class="flex gap-2 items-center py-2">
+                <input type="checkbox" checked={*enabled} {onchange} />
+                {"Keep verified proofs in this browser"}
+            </label>
+            <p class="text-gray-400 text-xs">
+                {"Proofs, including their disclosed data, are stored in this browser's IndexedDB. They are never uploaded. Turn this off to delete them."}
+            </p>
+            if *enabled {
+                {for groups.iter().map(|(domain, dates)| html! {
+                    <div class="pt-4">
+                        <p class="font-bold truncate" title={domain.clone()}>{domain}</p>
+                        {for dates.iter().rev().map(|(date, entries)| html! {
+                            <div class="pl-2 pt-1">
+                                <p class="text-gray-400">{date}</p>
+                                <ul class="pl-2">
+                                    {for entries.iter().map(|entry| view_entry(entry))}
+                                </ul>
+                            </div>
+                        })}
+                    </div>
+                })}
+                if !entries.is_empty() {
+                    <button class="mt-4 px-4 py-2 hover:bg-black hover:text-white rounded border-white border" onclick={on_clear.reform(|_| ())}>{"Delete history"}</button>
+                }
+            }
+        </aside>
+    }
+}
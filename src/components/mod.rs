@@ -1,4 +1,18 @@
+pub mod claims_card;
+pub mod compare_view;
 pub mod content_iframe;
+pub mod dashboard;
+pub mod disclosure_stats;
+pub mod history_sidebar;
+pub mod paste_input;
 pub mod pem_input;
+pub mod policy_input;
+pub mod policy_results;
+pub mod privacy_lint;
 pub mod redacted_bytes_component;
+pub mod redaction_settings;
+pub mod request_checks;
+pub mod search_bar;
+pub mod template_settings;
+pub mod time_series;
 pub mod view_file;
@@ -0,0 +1,69 @@
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::paste::decode_pasted;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Called with the contents of a `proof.json`
+    pub proof_callback: Callback<Vec<u8>>,
+}
+
+#[function_component(PasteInputComponent)]
+pub fn paste_input_component(Props { proof_callback }: &Props) -> Html {
+    let input_value = use_state(String::new);
+    let invalid_input = use_state(|| None);
+
+    let oninput = {
+        let input_value = input_value.clone();
+        let invalid_input = invalid_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            input_value.set(input.value());
+            invalid_input.set(None);
+        })
+    };
+
+    let onclick = {
+        let input_value = input_value.clone();
+        let invalid_input = invalid_input.clone();
+        let callback = proof_callback.clone();
+        Callback::from(move |_: MouseEvent| match decode_pasted(&input_value) {
+            Ok(proof) => {
+                input_value.set(String::new());
+                callback.emit(proof);
+            }
+            Err(err) => invalid_input.set(Some(err)),
+        })
+    };
+
+    // Toggling styles based on the presence of an error
+    let style = if invalid_input.is_none() {
+        "text-sm text-white border-gray-600 focus:ring-blue-500 focus:border-blue-500"
+    } else {
+        "text-sm text-red-500 border-red-500 focus:border-red-500 focus:ring-red-500"
+    };
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={false}>
+                    <summary class="cursor-pointer px-8 py-2"><b>{"Paste a proof:"}</b></summary>
+                    <div class="px-8">
+                        <p class="text-sm text-gray-400">{"JSON, a JSON envelope around the proof, or hex or base64 of a JSON or bincode proof"}</p>
+                        <textarea class={style.to_string() + " block p-2.5 w-full bg-zinc-700 mt-2 border rounded font-mono"}
+                            id="paste-input"
+                            rows="6"
+                            value={input_value.to_string()}
+                            oninput={oninput} >
+                        </textarea>
+                        if let Some(error_message) = invalid_input.as_ref() {
+                            <p class="mt-2 text-red-500">{error_message}</p>
+                        }
+                        <button class="mt-2 px-4 py-2 hover:bg-black hover:text-white rounded border-white border" disabled={input_value.trim().is_empty()} {onclick}>{"Load proof"}</button>
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
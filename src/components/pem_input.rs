@@ -8,6 +8,10 @@ use yew::prelude::*;
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub pem_callback: Callback<p256::PublicKey>,
+    /// Key to show instead of `DEFAULT_PEM`, e.g. from a link. The input follows it when it
+    /// changes to a different key.
+    #[prop_or_default]
+    pub initial_pem: Option<String>,
 }
 
 // from https://github.com/tlsnotary/notary-server/tree/main/src/fixture/notary/notary.key
@@ -18,10 +22,35 @@ cRzMG5kaTeHGoSzDu6cFqx3uEWYpFGo6C0EOUgf+mEgbktLrXocv5yHzKg==
 -----END PUBLIC KEY-----";
 
 #[function_component(PemInputComponent)]
-pub fn pem_input_component(Props { pem_callback }: &Props) -> Html {
-    let input_value = use_state(|| DEFAULT_PEM.to_string());
+pub fn pem_input_component(
+    Props {
+        pem_callback,
+        initial_pem,
+    }: &Props,
+) -> Html {
+    let input_value = use_state(|| {
+        initial_pem
+            .clone()
+            .map(|pem| pem.trim().to_string())
+            .unwrap_or_else(|| DEFAULT_PEM.to_string())
+    });
     let invalid_input = use_state(|| None);
 
+    // A link opened later may change the key
+    {
+        let input_value = input_value.clone();
+        let invalid_input = invalid_input.clone();
+        use_effect_with(initial_pem.clone(), move |initial_pem| {
+            if let Some(pem) = initial_pem {
+                let current = p256::PublicKey::from_public_key_pem(input_value.as_str()).ok();
+                if current != p256::PublicKey::from_public_key_pem(pem.trim()).ok() {
+                    input_value.set(pem.trim().to_string());
+                    invalid_input.set(None);
+                }
+            }
+        });
+    }
+
     let oninput = {
         let input_value = input_value.clone();
         let callback = pem_callback.clone();
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+use crate::policy::{self, Policy};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Called with the new policy, or `None` when the policy is removed
+    pub policy_callback: Callback<Option<Rc<Policy>>>,
+}
+
+const PLACEHOLDER: &str = r#"name = "Bank balance"
+
+[[rules]]
+type = "server_name"
+pattern = "api.bank.example"
+
+[[rules]]
+type = "time"
+max_age = 86400
+
+[[rules]]
+type = "request_line"
+pattern = "^GET /v1/accounts"
+
+[[rules]]
+type = "json"
+path = "$.status"
+equals = "ok""#;
+
+#[function_component(PolicyInputComponent)]
+pub fn policy_input_component(Props { policy_callback }: &Props) -> Html {
+    let input_value = use_state(|| policy::load_text().unwrap_or_default());
+    let invalid_input = use_state(|| None);
+
+    let oninput = {
+        let input_value = input_value.clone();
+        let invalid_input = invalid_input.clone();
+        let callback = policy_callback.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let value = input.value();
+            input_value.set(value.clone());
+
+            if value.trim().is_empty() {
+                policy::save_text(&value);
+                invalid_input.set(None);
+                callback.emit(None);
+                return;
+            }
+            match Policy::parse(&value) {
+                Ok(policy) => {
+                    policy::save_text(&value);
+                    invalid_input.set(None);
+                    callback.emit(Some(Rc::new(policy)));
+                }
+                // Keep the last valid policy while typing
+                Err(err) => invalid_input.set(Some(err)),
+            }
+        })
+    };
+
+    // Toggling styles based on the presence of an error
+    let style = if invalid_input.is_none() {
+        "text-sm text-white border-gray-600 focus:ring-blue-500 focus:border-blue-500"
+    } else {
+        "text-sm text-red-500 border-red-500 focus:border-red-500 focus:ring-red-500"
+    };
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={false}>
+                    <summary class="cursor-pointer px-8 py-2"><b>{"Policy:"}</b>{if invalid_input.is_some() {" ❌"} else {""}}</summary>
+                    <div class="px-8">
+                        <p class="text-sm text-gray-400">
+                            {"Rules every verified proof is checked against, in TOML or JSON. Rule types: server_name, time, notary, request_line, method, path, query, host, status_line, header, text and json."}
+                        </p>
+                        <textarea class={style.to_string() + " block p-2.5 w-full bg-zinc-700 mt-2 border rounded font-mono"}
+                            id="policy-input"
+                            rows="10"
+                            placeholder={PLACEHOLDER}
+                            value={input_value.to_string()}
+                            oninput={oninput} >
+                        </textarea>
+                        if let Some(error_message) = invalid_input.as_ref() {
+                            <p class="mt-2 text-red-500">{error_message}</p>
+                        }
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,63 @@
+use yew::prelude::*;
+
+use crate::policy::{Outcome, RuleResult};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub name: Option<String>,
+    pub results: Vec<RuleResult>,
+}
+
+/// The outcome of every rule of the policy on a proof
+#[function_component(PolicyResultsComponent)]
+pub fn policy_results_component(Props { name, results }: &Props) -> Html {
+    let count = |f: fn(&Outcome) -> bool| results.iter().filter(|r| f(&r.outcome)).count();
+    let passed = count(|outcome| *outcome == Outcome::Pass);
+    let failed = count(|outcome| matches!(outcome, Outcome::Fail(_)));
+    let undeterminable = results.len() - passed - failed;
+
+    let summary = format!(
+        "{}: {} passed, {} failed, {} undeterminable",
+        name.as_deref().unwrap_or("Policy"),
+        passed,
+        failed,
+        undeterminable
+    );
+    let (icon, summary_class) = if failed > 0 {
+        ("❌", "text-red-400")
+    } else if undeterminable > 0 {
+        ("❔", "text-yellow-400")
+    } else {
+        ("✅", "text-green-400")
+    };
+
+    let rows = results.iter().map(|result| {
+        let (icon, reason, class) = match &result.outcome {
+            Outcome::Pass => ("✅", String::new(), "text-green-400"),
+            Outcome::Fail(reason) => ("❌", reason.clone(), "text-red-400"),
+            Outcome::Undeterminable(reason) => ("❔", reason.clone(), "text-yellow-400"),
+        };
+        html! {
+            <tr class="border-t border-gray-600">
+                <td class="px-2 py-1">{icon}</td>
+                <td class="px-2 py-1 font-mono break-all">{result.rule.clone()}</td>
+                <td class={classes!("px-2", "py-1", class)}>{reason}</td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="p-4 w-5/6">
+            <details open={failed > 0 || undeterminable > 0}>
+                <summary class={classes!("cursor-pointer", summary_class)}>
+                    <b>{icon}{" "}{summary}</b>
+                </summary>
+                <table class="w-full text-sm mt-2">
+                    <tbody>
+                        {for rows}
+                    </tbody>
+                </table>
+            </details>
+        </div>
+    }
+}
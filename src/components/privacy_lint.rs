@@ -0,0 +1,76 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use crate::privacy::lint;
+use crate::verify::ProofReport;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub report: Rc<ProofReport>,
+}
+
+// Longest excerpt of a finding that is shown
+const EXCERPT_LEN: usize = 48;
+
+fn excerpt(text: &str) -> String {
+    match text.char_indices().nth(EXCERPT_LEN) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Secrets and personal data the proof discloses, for provers to check before sharing it
+#[function_component(PrivacyLintComponent)]
+pub fn privacy_lint_component(Props { report }: &Props) -> Html {
+    let findings = use_memo(report.clone(), |report| match &report.result {
+        Ok(proof) => lint(proof),
+        Err(_) => Vec::new(),
+    });
+
+    if report.result.is_err() {
+        return html! {};
+    }
+
+    let (summary, summary_class) = match findings.len() {
+        0 => (
+            "✅ Privacy: no secrets found in the disclosed bytes".to_string(),
+            "text-green-400",
+        ),
+        count => (
+            format!(
+                "⚠️ Privacy: {} possible secrets disclosed, check them before sharing the proof",
+                count
+            ),
+            "text-yellow-400",
+        ),
+    };
+
+    let rows = findings.iter().map(|finding| {
+        html! {
+            <tr class="border-t border-gray-600">
+                <td class="px-2 py-1">{finding.kind.to_string()}</td>
+                <td class="px-2 py-1 text-gray-400 font-mono whitespace-nowrap">
+                    {format!("{} bytes {}..{}", finding.direction, finding.range.start, finding.range.end)}
+                </td>
+                <td class="px-2 py-1 font-mono break-all">{excerpt(&finding.text)}</td>
+                <td class="px-2 py-1 text-gray-400">{finding.kind.suggestion()}{" when generating the proof again."}</td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="p-4 w-5/6">
+            <details open={!findings.is_empty()}>
+                <summary class={classes!("cursor-pointer", summary_class)}>
+                    <b>{summary}</b>
+                </summary>
+                <table class="w-full text-sm mt-2">
+                    <tbody>
+                        {for rows}
+                    </tbody>
+                </table>
+            </details>
+        </div>
+    }
+}
@@ -1,9 +1,17 @@
+use std::rc::Rc;
 use std::{fmt, ops::Range};
 
 use gloo::console::log;
+use serde::{Deserialize, Serialize};
+use web_sys::Element;
 use yew::prelude::*;
 
-#[derive(Clone, PartialEq)]
+use crate::charset::TranscriptDecoder;
+use crate::highlight::{highlight, redaction_selector, Highlights, Language};
+use crate::redaction::{marked_chars, surrounding_openings, Opening, RedactionStyle};
+use crate::verify::{ProofReport, Transcript};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Send,
     Received,
@@ -21,73 +29,42 @@ impl fmt::Display for Direction {
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub direction: Direction,
-    pub redacted_char: char,
-    pub bytes: Vec<u8>,
-    pub redacted_ranges: Vec<Range<usize>>,
+    pub redaction: RedactionStyle,
+    /// The verified proof whose transcript of `direction` is shown
+    pub report: Rc<ProofReport>,
+    #[prop_or_default]
+    pub highlights: Highlights,
 }
 
-fn get_redacted_string(redacted_char: &char, size: usize) -> String {
-    if true || size < 10 {
-        redacted_char.to_string().repeat(size)
+/// Render a run of redacted characters
+pub fn redacted_span(text: String, style: &RedactionStyle) -> Html {
+    if style.boxes {
+        html! {
+            <span class="rounded-sm border border-red-500 bg-red-500/20 text-red-300">{text}</span>
+        }
     } else {
-        format! {"{}...{}", redacted_char.to_string().repeat(3), redacted_char.to_string().repeat(3)}
+        html! {
+            <span style="color:red;">{text}</span>
+        }
     }
 }
 
-fn redactions_in_red(
-    bytes: &Vec<u8>,
-    redacted_ranges: &Vec<Range<usize>>,
-    redacted_char: &char,
-) -> Html {
-    if redacted_ranges.is_empty() {
-        return Html::from(String::from_utf8(bytes.to_vec()).unwrap());
-    }
-
-    // create ranges for non redacted parts and store last redacted position
-    let (non_redacted_ranges, last_redacted_position) = redacted_ranges.iter().fold(
-        (Vec::new(), 0), // (Accumulator vector, last redacted position)
-        |(mut acc, last_end), range| {
-            acc.push(Range {
-                start: last_end,
-                end: range.start,
-            });
-            (acc, range.end)
-        },
-    );
-
-    // interweave the redacted and non-redacted ranges
-    let all_ranges = non_redacted_ranges
-        .into_iter()
-        .zip(redacted_ranges.iter())
-        .flat_map(|(non_redacted, redacted)| {
-            vec![
-                (non_redacted.start, non_redacted.end, false),
-                (redacted.start, redacted.end, true),
-            ]
-        })
-        .chain(std::iter::once((
-            last_redacted_position,
-            bytes.len(),
-            false,
-        ))); // Handle remaining non-redacted part
-
-    let html_nodes = all_ranges
-        .map(|(start, end, is_redacted)| {
-            if is_redacted {
-                Html::from_html_unchecked(AttrValue::from(format!(
-                    "<span style=\"color:red;\">{}</span>",
-                    get_redacted_string(redacted_char, end - start)
-                )))
-            } else {
-                Html::from(String::from_utf8_lossy(&bytes[start..end]))
-            }
-        })
-        .collect::<Vec<_>>();
+/// Describe the opened ranges around a redacted range
+fn describe_openings(openings: &[Opening], range: &Range<usize>) -> String {
+    let describe = |(opening, range): (&Opening, Range<usize>)| {
+        format!(
+            "opening #{} (bytes {}..{})",
+            opening.id, range.start, range.end
+        )
+    };
 
-    html! {
-        <>
-            { for html_nodes }
-        </>
+    match surrounding_openings(openings, range) {
+        (Some(before), Some(after)) => {
+            format!("Between {} and {}", describe(before), describe(after))
+        }
+        (Some(before), None) => format!("After {}, up to the end", describe(before)),
+        (None, Some(after)) => format!("From the start up to {}", describe(after)),
+        (None, None) => "Not next to any opened range".to_string(),
     }
 }
 
@@ -95,17 +72,119 @@ fn redactions_in_red(
 pub fn RedactedBytesComponent(props: &Props) -> Html {
     let Props {
         direction,
-        redacted_char,
-        bytes,
-        redacted_ranges,
+        redaction,
+        report,
+        highlights,
     } = props;
 
+    // Decoded once per proof and style, highlights change with every search
+    let chars = use_memo(
+        (report.clone(), direction.clone(), *redaction),
+        |(report, direction, redaction)| match &report.result {
+            Ok(proof) => {
+                let Transcript { bytes, redacted } = proof.transcript(direction);
+                let decoder = TranscriptDecoder::new(bytes);
+                marked_chars(bytes, &decoder, 0..bytes.len(), redacted, redaction)
+            }
+            Err(_) => Vec::new(),
+        },
+    );
+    // Commitment openings of this direction, to describe where the redactions are
+    let openings = use_memo(
+        (report.clone(), direction.clone()),
+        |(report, direction)| match &report.result {
+            Ok(proof) => proof.openings(direction),
+            Err(_) => Vec::new(),
+        },
+    );
+    let selected = use_state(|| None::<Range<usize>>);
+    let bytes_ref = use_node_ref();
+
+    // Bring the selected redaction into view
+    {
+        let bytes_ref = bytes_ref.clone();
+        use_effect_with((*selected).clone(), move |selected| {
+            let element = selected.as_ref().and_then(|range| {
+                bytes_ref
+                    .cast::<Element>()?
+                    .query_selector(&redaction_selector(range.start))
+                    .ok()
+                    .flatten()
+            });
+            if let Some(element) = element {
+                element.scroll_into_view();
+            }
+        });
+    }
+
+    let select = {
+        let selected = selected.clone();
+        Callback::from(move |range: Range<usize>| selected.set(Some(range)))
+    };
+
+    let Ok(proof) = &report.result else {
+        return html! {};
+    };
+    let redacted_ranges = &proof.transcript(direction).redacted;
+
+    let highlights = Highlights {
+        redacted_ranges: redacted_ranges.clone(),
+        selected_redaction: (*selected).clone(),
+        on_select_redaction: Some(select.clone()),
+        ..highlights.clone()
+    };
+
+    let inspector = (*selected).clone().map(|range| {
+        let onclose = {
+            let selected = selected.clone();
+            Callback::from(move |_: MouseEvent| selected.set(None))
+        };
+        html! {
+            <div class="mt-2 p-2 border border-red-400 rounded text-sm flex items-start gap-4">
+                <div class="flex-1">
+                    <p><b>{"Redacted "}{direction}{" bytes "}{range.start}{".."}{range.end}</b>{format!(" ({} bytes)", range.len())}</p>
+                    <p class="text-gray-400">{describe_openings(&openings, &range)}</p>
+                </div>
+                <button class="px-2 hover:bg-black rounded" title="Close" onclick={onclose}>{"✕"}</button>
+            </div>
+        }
+    });
+
     html! {
-        <details class="p-4 w-5/6" open={true}>
+        <details class="p-4 w-5/6" open={true} data-direction={direction.to_string()}>
             <summary><b>{"Bytes "}{direction}{": " }</b></summary>
-            <div class="bg-black text-white p-4 rounded-md overflow-x-auto">
-                <pre>{redactions_in_red(bytes, redacted_ranges, redacted_char)}</pre>
+            <div class="flex gap-4">
+                <div ref={bytes_ref} class="flex-1 min-w-0 bg-black text-white p-4 rounded-md overflow-x-auto">
+                    <pre>{highlight(&chars, Language::Http, &highlights, redaction)}</pre>
+                </div>
+                if !redacted_ranges.is_empty() {
+                    <aside class="w-48 shrink-0 text-sm">
+                        <p class="font-semibold mb-1">{format!("{} redacted ranges", redacted_ranges.len())}</p>
+                        <ul class="max-h-96 overflow-y-auto">
+                            {for redacted_ranges.iter().map(|range| {
+                                let onclick = {
+                                    let select = select.clone();
+                                    let range = range.clone();
+                                    Callback::from(move |_: MouseEvent| select.emit(range.clone()))
+                                };
+                                let is_selected = selected.as_ref() == Some(range);
+                                html! {
+                                    <li>
+                                        <button
+                                            class={classes!("w-full", "text-left", "px-2", "rounded", "font-mono", "hover:bg-black", is_selected.then_some("bg-black"))}
+                                            {onclick}
+                                        >
+                                            <span style="color:red;">{format!("{}..{}", range.start, range.end)}</span>
+                                            <span class="text-gray-400">{format!(" {} B", range.len())}</span>
+                                        </button>
+                                    </li>
+                                }
+                            })}
+                        </ul>
+                    </aside>
+                }
             </div>
+            {for inspector}
         </details>
     }
 }
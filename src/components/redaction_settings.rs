@@ -0,0 +1,96 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::redaction::RedactionStyle;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub style: RedactionStyle,
+    pub style_callback: Callback<RedactionStyle>,
+}
+
+// Glyphs offered in the dropdown, anything else is a custom glyph
+const GLYPHS: [(char, &str); 3] = [('X', "X"), ('█', "█ (block)"), ('·', "· (dot)")];
+
+#[function_component(RedactionSettingsComponent)]
+pub fn redaction_settings_component(
+    Props {
+        style,
+        style_callback,
+    }: &Props,
+) -> Html {
+    // Custom keeps the current glyph until another one is typed
+    let custom_selected = use_state(|| false);
+    let is_custom = *custom_selected || !GLYPHS.iter().any(|(glyph, _)| *glyph == style.glyph);
+
+    let onchange_glyph = {
+        let style = *style;
+        let callback = style_callback.clone();
+        let custom_selected = custom_selected.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let value = select.value();
+            custom_selected.set(value == "custom");
+            if let Some(glyph) = value.chars().next().filter(|_| value != "custom") {
+                callback.emit(RedactionStyle { glyph, ..style });
+            }
+        })
+    };
+
+    let oninput_custom = {
+        let style = *style;
+        let callback = style_callback.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(glyph) = input.value().chars().find(|c| !c.is_whitespace()) {
+                callback.emit(RedactionStyle { glyph, ..style });
+            }
+        })
+    };
+
+    let toggle = |update: fn(RedactionStyle, bool) -> RedactionStyle| {
+        let style = *style;
+        let callback = style_callback.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            callback.emit(update(style, input.checked()));
+        })
+    };
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={false}>
+                    <summary class="cursor-pointer px-8 py-2"><b>{"Redaction display:"}</b></summary>
+                    <div class="px-8 flex flex-wrap gap-6 items-center text-sm">
+                        <label class="flex gap-2 items-center">
+                            {"Glyph"}
+                            <select class="p-2 bg-zinc-700 border border-gray-600 rounded" onchange={onchange_glyph}>
+                                {for GLYPHS.iter().map(|(glyph, label)| html! {
+                                    <option value={glyph.to_string()} selected={!is_custom && *glyph == style.glyph}>{label}</option>
+                                })}
+                                <option value="custom" selected={is_custom}>{"Custom"}</option>
+                            </select>
+                            if is_custom {
+                                <input
+                                    class="w-12 p-2 bg-zinc-700 border border-gray-600 rounded text-center"
+                                    maxlength="2"
+                                    value={style.glyph.to_string()}
+                                    oninput={oninput_custom}
+                                />
+                            }
+                        </label>
+                        <label class="flex gap-2 items-center">
+                            <input type="checkbox" checked={style.collapse} onchange={toggle(|style, collapse| RedactionStyle { collapse, ..style })} />
+                            {"Collapse long redactions"}
+                        </label>
+                        <label class="flex gap-2 items-center">
+                            <input type="checkbox" checked={style.boxes} onchange={toggle(|style, boxes| RedactionStyle { boxes, ..style })} />
+                            {"Show as placeholder boxes"}
+                        </label>
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
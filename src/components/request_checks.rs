@@ -0,0 +1,187 @@
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::policy_results::PolicyResultsComponent;
+use crate::policy::{Policy, Rule};
+use crate::request::{check_host, parse_request, HostCheck, Part};
+use crate::verify::ProofReport;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub report: Rc<ProofReport>,
+}
+
+/// What the reviewer expects of the request
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Expected {
+    method: String,
+    /// Pattern of the path
+    path: String,
+    /// One parameter per line, `name` or `name=pattern`
+    query: String,
+    /// Host name, where `*` stands for any part of it
+    host: String,
+}
+
+impl Expected {
+    fn rules(&self) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        if !self.method.trim().is_empty() {
+            rules.push(Rule::Method {
+                method: self.method.trim().to_string(),
+            });
+        }
+        if !self.path.trim().is_empty() {
+            rules.push(Rule::Path {
+                pattern: self.path.trim().to_string(),
+            });
+        }
+        for line in self.query.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, pattern) = match line.split_once('=') {
+                Some((name, pattern)) => (name, Some(pattern.trim().to_string())),
+                None => (line, None),
+            };
+            rules.push(Rule::Query {
+                name: name.trim().to_string(),
+                pattern,
+            });
+        }
+        if !self.host.trim().is_empty() {
+            rules.push(Rule::Host {
+                pattern: Some(self.host.trim().to_string()),
+            });
+        }
+        rules
+    }
+}
+
+fn view_part(part: &Part) -> Html {
+    if part.redacted {
+        html! { <span style="color:red;" title="Partly redacted">{part.text.clone()}</span> }
+    } else {
+        html! { {part.text.clone()} }
+    }
+}
+
+/// The method, path, query and Host header of the request, and checks on them
+#[function_component(RequestChecksComponent)]
+pub fn request_checks_component(Props { report }: &Props) -> Html {
+    let expected = use_state(Expected::default);
+
+    let Ok(proof) = &report.result else {
+        return html! {};
+    };
+    let Some(request) = parse_request(&proof.sent) else {
+        return html! {
+            <div class="p-4 w-5/6">
+                <b>{"Request:"}</b>
+                <div class="bg-black text-white p-4 rounded-md text-gray-400">{"The sent transcript does not start with an HTTP request"}</div>
+            </div>
+        };
+    };
+
+    let host_check = match check_host(&request, &proof.server_name) {
+        HostCheck::Matches => html! {
+            <p class="text-green-400">{"✅ The Host header is the server name checked against the certificate"}</p>
+        },
+        HostCheck::Differs(host) => html! {
+            <p class="text-red-400 font-bold">
+                {format!("⚠️ The Host header {} differs from the server name {} checked against the certificate: the request may have been meant for another site", host, proof.server_name)}
+            </p>
+        },
+        HostCheck::Redacted => html! {
+            <p class="text-yellow-400">{"❔ The Host header or some header names are redacted, the request may have been sent with another Host header than the server name"}</p>
+        },
+        HostCheck::Missing => html! {
+            <p class="text-yellow-400">{"❔ The request has no Host header"}</p>
+        },
+    };
+
+    let set = |update: fn(&mut Expected, String)| {
+        let expected = expected.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = match e.target_dyn_into::<HtmlTextAreaElement>() {
+                Some(textarea) => textarea.value(),
+                None => e.target_unchecked_into::<HtmlInputElement>().value(),
+            };
+            let mut new_expected = (*expected).clone();
+            update(&mut new_expected, value);
+            expected.set(new_expected);
+        })
+    };
+
+    let rules = expected.rules();
+    let results = if rules.is_empty() {
+        html! {}
+    } else {
+        match Policy::new(None, rules) {
+            Ok(policy) => {
+                let now = (js_sys::Date::now() / 1000.0) as u64;
+                html! {
+                    <PolicyResultsComponent name={Some("Request checks".to_string())} results={policy.evaluate(proof, &report.notary, now)} />
+                }
+            }
+            Err(err) => html! { <p class="px-4 text-sm text-red-500">{err}</p> },
+        }
+    };
+
+    let row = |label: &str, value: Html| {
+        html! {
+            <tr class="border-t border-gray-600">
+                <td class="px-2 py-1 w-32">{label.to_string()}</td>
+                <td class="px-2 py-1 font-mono break-all">{value}</td>
+            </tr>
+        }
+    };
+    let input_class = "p-2 bg-zinc-700 border border-gray-600 rounded font-mono";
+
+    html! {
+        <>
+        <div class="p-4 w-5/6">
+            <b>{"Request:"}</b>
+            <div class="bg-black text-white p-4 rounded-md text-sm">
+                <table class="w-full">
+                    <tbody>
+                        {row("Method", view_part(&request.method))}
+                        {row("Path", view_part(&request.path))}
+                        {for request.parameters.iter().map(|(name, value)| row("Query", html! {
+                            <>
+                                {view_part(name)}
+                                if let Some(value) = value {
+                                    {"="}{view_part(value)}
+                                }
+                            </>
+                        }))}
+                        {row("Host", request.host.as_ref().map(view_part).unwrap_or_else(|| html! { <span class="text-gray-400">{"none"}</span> }))}
+                    </tbody>
+                </table>
+                <div class="mt-2">{host_check}</div>
+            </div>
+            <details class="mt-2">
+                <summary class="cursor-pointer text-sm">{"Check the request"}</summary>
+                <div class="grid grid-cols-2 gap-2 mt-2 text-sm">
+                    <label class="flex flex-col gap-1">
+                        {"Method"}
+                        <input class={input_class} type="text" placeholder="GET" value={expected.method.clone()} oninput={set(|expected, value| expected.method = value)} />
+                    </label>
+                    <label class="flex flex-col gap-1">
+                        {"Path pattern"}
+                        <input class={input_class} type="text" placeholder="^/api/user/123$" value={expected.path.clone()} oninput={set(|expected, value| expected.path = value)} />
+                    </label>
+                    <label class="flex flex-col gap-1">
+                        {"Query parameters, one name or name=pattern per line"}
+                        <textarea class={input_class} rows="3" placeholder="id=^123$" value={expected.query.clone()} oninput={set(|expected, value| expected.query = value)} />
+                    </label>
+                    <label class="flex flex-col gap-1">
+                        {"Host"}
+                        <input class={input_class} type="text" placeholder={proof.server_name.clone()} value={expected.host.clone()} oninput={set(|expected, value| expected.host = value)} />
+                    </label>
+                </div>
+            </details>
+        </div>
+        {results}
+        </>
+    }
+}
@@ -0,0 +1,120 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::search::SearchMode;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Number of matches in both transcripts
+    pub matches: usize,
+    /// Number of matches that overlap a redacted range
+    pub possible_matches: usize,
+    /// Status of the selected match
+    pub current: Option<String>,
+    /// Invalid query
+    pub error: Option<String>,
+    pub on_search: Callback<(String, SearchMode)>,
+    /// Step through the matches, `1` for the next match, `-1` for the previous one
+    pub on_step: Callback<isize>,
+}
+
+#[function_component(SearchBar)]
+pub fn search_bar(props: &Props) -> Html {
+    let Props {
+        matches,
+        possible_matches,
+        current,
+        error,
+        on_search,
+        on_step,
+    } = props;
+
+    let query = use_state(String::new);
+    let mode = use_state(SearchMode::default);
+
+    let oninput = {
+        let query = query.clone();
+        let mode = mode.clone();
+        let on_search = on_search.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            on_search.emit((input.value(), *mode));
+        })
+    };
+
+    let onchange = {
+        let query = query.clone();
+        let mode = mode.clone();
+        let on_search = on_search.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let new_mode = if select.value() == "hex" {
+                SearchMode::Hex
+            } else {
+                SearchMode::Text
+            };
+            mode.set(new_mode);
+            on_search.emit(((*query).clone(), new_mode));
+        })
+    };
+
+    // Enter steps to the next match, Shift+Enter to the previous one
+    let onkeydown = {
+        let on_step = on_step.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Enter" {
+                on_step.emit(if e.shift_key() { -1 } else { 1 });
+            }
+        })
+    };
+
+    let step = |step: isize| {
+        let on_step = on_step.clone();
+        Callback::from(move |_: MouseEvent| on_step.emit(step))
+    };
+
+    let status = match (error, query.is_empty()) {
+        (Some(error), _) => error.clone(),
+        (None, true) => String::new(),
+        (None, false) if *matches == 0 => "No matches".to_string(),
+        (None, false) => {
+            let mut status = format!("{} matches", matches);
+            if *possible_matches > 0 {
+                status.push_str(&format!(
+                    ", {} of which overlap redacted bytes and may not be in the original transcript",
+                    possible_matches
+                ));
+            }
+            if let Some(current) = current {
+                status = format!("{} — {}", current, status);
+            }
+            status
+        }
+    };
+
+    let button_classes =
+        "px-3 py-1 rounded border border-gray-600 hover:bg-black disabled:opacity-50";
+
+    html! {
+        <div class="p-4 w-5/6">
+            <div class="flex gap-2 items-center">
+                <input
+                    type="search"
+                    class={classes!("flex-1", "p-2", "bg-zinc-800", "border", "rounded", "text-sm", if error.is_some() { "border-red-500" } else { "border-gray-600" })}
+                    placeholder={if *mode == SearchMode::Hex { "Search bytes, e.g. 48 54 54 50" } else { "Search transcripts" }}
+                    value={(*query).clone()}
+                    {oninput}
+                    {onkeydown}
+                />
+                <select class="p-2 bg-zinc-800 border border-gray-600 rounded text-sm" {onchange}>
+                    <option value="text" selected={*mode == SearchMode::Text}>{"Text"}</option>
+                    <option value="hex" selected={*mode == SearchMode::Hex}>{"Hex"}</option>
+                </select>
+                <button class={button_classes} title="Previous match" disabled={*matches == 0} onclick={step(-1)}>{"↑"}</button>
+                <button class={button_classes} title="Next match" disabled={*matches == 0} onclick={step(1)}>{"↓"}</button>
+            </div>
+            <p class={classes!("text-sm", "mt-1", if error.is_some() { "text-red-500" } else { "text-gray-400" })}>{status}</p>
+        </div>
+    }
+}
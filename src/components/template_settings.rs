@@ -0,0 +1,147 @@
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::claims::template::{self, StoredTemplate, Template};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Called with the valid templates whenever templates are added or removed
+    pub templates_callback: Callback<Vec<Template>>,
+}
+
+/// Upload, list and remove the claim templates, which are kept in the browser
+#[function_component(TemplateSettingsComponent)]
+pub fn template_settings_component(Props { templates_callback }: &Props) -> Html {
+    let stored = use_state(template::load);
+    let upload_errors = use_state(Vec::<String>::new);
+
+    let onchange = {
+        let stored = stored.clone();
+        let upload_errors = upload_errors.clone();
+        let callback = templates_callback.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(files) = input.files() else {
+                return;
+            };
+            let files: Vec<web_sys::File> =
+                (0..files.length()).filter_map(|i| files.get(i)).collect();
+            // Let the same file be uploaded again after it was edited
+            input.set_value("");
+
+            let stored = stored.clone();
+            let upload_errors = upload_errors.clone();
+            let callback = callback.clone();
+            spawn_local(async move {
+                let mut templates = (*stored).clone();
+                let mut errors = Vec::new();
+                for file in files {
+                    let text = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|text| text.as_string());
+                    let Some(text) = text else {
+                        errors.push(format!("{}: the file could not be read", file.name()));
+                        continue;
+                    };
+                    let uploaded = StoredTemplate {
+                        file_name: file.name(),
+                        text,
+                    };
+                    if let Err(err) = uploaded.parse() {
+                        errors.push(format!("{}: {}", uploaded.file_name, err));
+                        continue;
+                    }
+                    // Uploading a file again replaces it
+                    match templates
+                        .iter_mut()
+                        .find(|template| template.file_name == uploaded.file_name)
+                    {
+                        Some(existing) => *existing = uploaded,
+                        None => templates.push(uploaded),
+                    }
+                }
+
+                template::save(&templates);
+                callback.emit(template::parse_all(&templates));
+                stored.set(templates);
+                upload_errors.set(errors);
+            });
+        })
+    };
+
+    let remove = |index: usize| {
+        let stored = stored.clone();
+        let callback = templates_callback.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut templates = (*stored).clone();
+            templates.remove(index);
+            template::save(&templates);
+            callback.emit(template::parse_all(&templates));
+            stored.set(templates);
+        })
+    };
+
+    let invalid = stored
+        .iter()
+        .filter(|stored| stored.parse().is_err())
+        .count();
+
+    let rows = stored.iter().enumerate().map(|(index, stored)| {
+        let description = match stored.parse() {
+            Ok(template) => html! {
+                <>
+                    <td class="px-2 py-1">{template.name.clone()}</td>
+                    <td class="px-2 py-1 font-mono">{template.server_name.clone()}</td>
+                    <td class="px-2 py-1 font-mono">{template.path.clone().unwrap_or_else(|| "any path".to_string())}</td>
+                    <td class="px-2 py-1">{format!("{} fields", template.field_count())}</td>
+                </>
+            },
+            Err(err) => html! {
+                <td class="px-2 py-1 text-red-400" colspan="4">{"❌ "}{err}</td>
+            },
+        };
+        html! {
+            <tr class="border-t border-gray-600">
+                <td class="px-2 py-1 text-gray-400">{stored.file_name.clone()}</td>
+                {description}
+                <td class="px-2 py-1 text-right">
+                    <button class="px-2 hover:bg-black rounded" title="Remove the template" onclick={remove(index)}>{"✕"}</button>
+                </td>
+            </tr>
+        }
+    });
+
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                // Open with the errors when stored templates no longer parse, e.g. after an update
+                <details class="w-full" open={invalid > 0}>
+                    <summary class="cursor-pointer px-8 py-2">
+                        <b>{format!("Claim templates: {}", stored.len())}</b>
+                        if invalid > 0 {
+                            <span class="text-red-400">{format!(" ({} invalid, not applied)", invalid)}</span>
+                        }
+                    </summary>
+                    <div class="px-8">
+                        <p class="text-sm text-gray-400">
+                            {"Template files describe the claims of a service in TOML or JSON: a name, a server_name where * matches any part, an optional path pattern, and fields with a label and one of json (a path like $.data.balance), regex or header. They are kept in this browser and applied to every matching proof."}
+                        </p>
+                        <input class="mt-2 text-sm" type="file" accept=".toml,.json,application/json" multiple={true} {onchange} />
+                        {for upload_errors.iter().map(|error| html! {
+                            <p class="mt-2 text-sm text-red-500">{error}</p>
+                        })}
+                        if !stored.is_empty() {
+                            <table class="w-full text-sm mt-2">
+                                <tbody>
+                                    {for rows}
+                                </tbody>
+                            </table>
+                        }
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,245 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::compare::Candidate;
+use crate::components::dashboard::scroll_to_card;
+use crate::export::download;
+use crate::series::{Extractor, Sample, Series};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// The loaded proofs, verified or not
+    pub proofs: Vec<Candidate>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Json,
+    Regex,
+}
+
+// Size of the chart, in SVG units
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN: f64 = 24.0;
+
+/// Server names of the verified proofs, the most common first
+fn server_names(proofs: &[Candidate]) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for proof in proofs {
+        let Ok(verified) = &proof.report.result else {
+            continue;
+        };
+        match counts
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&verified.server_name))
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((verified.server_name.clone(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.into_iter().map(|(name, _)| name).collect()
+}
+
+/// A line chart of the numbers over notarization time
+fn view_chart(series: &Series) -> Html {
+    let plotted = series.plotted();
+    if plotted.is_empty() {
+        return html! {
+            <p class="text-sm text-gray-400 py-2">{"No numbers to plot"}</p>
+        };
+    }
+
+    let (min_time, max_time) = plotted
+        .iter()
+        .fold((u64::MAX, 0), |(min, max), &(time, _)| {
+            (min.min(time), max.max(time))
+        });
+    let (min_value, max_value) = plotted
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), &(_, value)| {
+            (min.min(value), max.max(value))
+        });
+    let scale = |value: f64, min: f64, max: f64, size: f64| {
+        if max > min {
+            (value - min) / (max - min) * (size - 2.0 * MARGIN)
+        } else {
+            (size - 2.0 * MARGIN) / 2.0
+        }
+    };
+    let x = |time: u64| MARGIN + scale(time as f64, min_time as f64, max_time as f64, WIDTH);
+    let y = |value: f64| HEIGHT - MARGIN - scale(value, min_value, max_value, HEIGHT);
+
+    let line = plotted
+        .iter()
+        .map(|&(time, value)| format!("{:.1},{:.1}", x(time), y(value)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let date = |time: u64| {
+        series
+            .points
+            .iter()
+            .find(|point| point.time == Some(time))
+            .map(|point| point.time_string())
+            .unwrap_or_default()
+    };
+
+    html! {
+        <svg class="w-full bg-black rounded-md my-2" viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)}>
+            <polyline points={line} fill="none" stroke="#4ade80" stroke-width="2" />
+            {for plotted.iter().map(|&(time, value)| html! {
+                <circle cx={format!("{:.1}", x(time))} cy={format!("{:.1}", y(value))} r="3" fill="#4ade80">
+                    <title>{format!("{}: {}", date(time), value)}</title>
+                </circle>
+            })}
+            <text x="4" y="14" fill="#9ca3af" font-size="10">{max_value.to_string()}</text>
+            <text x="4" y={(HEIGHT - MARGIN - 4.0).to_string()} fill="#9ca3af" font-size="10">{min_value.to_string()}</text>
+            <text x="4" y={(HEIGHT - 4.0).to_string()} fill="#9ca3af" font-size="10">{date(min_time)}</text>
+            <text x={(WIDTH - 4.0).to_string()} y={(HEIGHT - 4.0).to_string()} fill="#9ca3af" font-size="10" text-anchor="end">{date(max_time)}</text>
+        </svg>
+    }
+}
+
+/// Plot a value of the responses of one domain over notarization time, e.g. a balance
+/// notarized every day
+#[function_component(TimeSeriesComponent)]
+pub fn time_series_component(Props { proofs }: &Props) -> Html {
+    let names = server_names(proofs);
+    let server_name = use_state(|| names.first().cloned());
+    let kind = use_state(|| Kind::Json);
+    let expression = use_state(String::new);
+
+    // The selected domain is no longer loaded
+    let selected = server_name
+        .as_ref()
+        .filter(|name| names.contains(name))
+        .or(names.first())
+        .cloned();
+
+    let onchange_server = {
+        let server_name = server_name.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            server_name.set(Some(select.value()));
+        })
+    };
+    let onchange_kind = {
+        let kind = kind.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            kind.set(if select.value() == "regex" {
+                Kind::Regex
+            } else {
+                Kind::Json
+            });
+        })
+    };
+    let oninput_expression = {
+        let expression = expression.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            expression.set(input.value());
+        })
+    };
+
+    let extractor = match (*kind, expression.trim()) {
+        (_, "") => None,
+        (Kind::Json, path) => Some(Extractor::json(path)),
+        (Kind::Regex, pattern) => Some(Extractor::regex(pattern)),
+    };
+
+    let result = match (extractor, &selected) {
+        (Some(Ok(extractor)), Some(server_name)) => {
+            let series = Series::extract(&extractor, server_name, proofs);
+            let export_csv = {
+                let csv = series.csv();
+                let file_name = format!("{}.series.csv", series.server_name);
+                Callback::from(move |_: MouseEvent| download(&file_name, "text/csv", &csv))
+            };
+            let sample_class = |sample: &Sample| match sample {
+                Sample::Value { .. } => "text-green-400",
+                Sample::Redacted(_) => "text-red-400",
+                Sample::Missing(_) => "text-gray-400",
+                Sample::Failed(_) => "text-red-500",
+            };
+            html! {
+                <>
+                    {view_chart(&series)}
+                    <div class="flex justify-end">
+                        <button class="px-4 py-2 hover:bg-black hover:text-white rounded border-white border" onclick={export_csv}>{"Export CSV"}</button>
+                    </div>
+                    <table class="w-full text-sm mt-2">
+                        <thead>
+                            <tr>
+                                <th class="px-2 py-1 text-left">{"File"}</th>
+                                <th class="px-2 py-1 text-left">{"Notarization time"}</th>
+                                <th class="px-2 py-1 text-left">{"Value"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {for series.points.iter().map(|point| {
+                                let id = point.id;
+                                let scroll = Callback::from(move |_: MouseEvent| scroll_to_card(id));
+                                let value = match &point.sample {
+                                    Sample::Value { text, number: Some(_) } => text.clone(),
+                                    Sample::Value { text, number: None } => format!("{} (not a number, not plotted)", text),
+                                    Sample::Redacted(reason) => format!("redacted: {}", reason),
+                                    Sample::Missing(reason) => format!("missing: {}", reason),
+                                    Sample::Failed(reason) => format!("failed verification: {}", reason),
+                                };
+                                html! {
+                                    <tr class="border-t border-gray-600 cursor-pointer hover:bg-zinc-600" onclick={scroll}>
+                                        <td class="px-2 py-1">{point.name.clone()}</td>
+                                        <td class="px-2 py-1">{point.time_string()}</td>
+                                        <td class={classes!("px-2", "py-1", "font-mono", "break-all", sample_class(&point.sample))}>{value}</td>
+                                    </tr>
+                                }
+                            })}
+                        </tbody>
+                    </table>
+                    if series.unattributed > 0 {
+                        <p class="text-sm text-gray-400 py-2">
+                            {format!("{} proofs that did not verify are not listed, their file names do not name {}", series.unattributed, series.server_name)}
+                        </p>
+                    }
+                </>
+            }
+        }
+        (Some(Err(err)), _) => html! {
+            <p class="text-sm text-red-400 py-2">{"❌ "}{err}</p>
+        },
+        _ => html! {
+            <p class="text-sm text-gray-400 py-2">{"Enter a JSON path like $.data.balance, or a regular expression with a capture group"}</p>
+        },
+    };
+
+    let input_class = "p-2 bg-zinc-700 border border-gray-600 rounded";
+    html! {
+        <div class="container flex mx-auto p-4">
+            <div class="w-full">
+                <details class="w-full" open={false}>
+                    <summary class="cursor-pointer px-8 py-2"><b>{"Time series"}</b></summary>
+                    <div class="px-8">
+                        <div class="flex flex-wrap gap-4 items-center text-sm">
+                            <label class="flex gap-2 items-center">
+                                {"Domain"}
+                                <select class={input_class} onchange={onchange_server}>
+                                    {for names.iter().map(|name| html! {
+                                        <option value={name.clone()} selected={selected.as_ref() == Some(name)}>{name.clone()}</option>
+                                    })}
+                                </select>
+                            </label>
+                            <select class={input_class} onchange={onchange_kind}>
+                                <option value="json" selected={*kind == Kind::Json}>{"JSON path"}</option>
+                                <option value="regex" selected={*kind == Kind::Regex}>{"Regex"}</option>
+                            </select>
+                            <input class={classes!(input_class, "flex-1", "font-mono")} type="text" value={(*expression).clone()} oninput={oninput_expression} />
+                        </div>
+                        {result}
+                    </div>
+                </details>
+            </div>
+        </div>
+    }
+}
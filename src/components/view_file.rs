@@ -1,135 +1,456 @@
 extern crate base64;
-use std::ops::Range;
-use std::str;
+use std::rc::Rc;
 use web_time::Duration;
 
-use yew::{function_component, html, Html, Properties};
+use web_sys::Element;
 
-use tlsn_core::proof::{SessionProof, TlsProof};
+use yew::{
+    classes, function_component, html, use_effect_with, use_memo, use_node_ref, use_state,
+    Callback, Html, MouseEvent, NodeRef, Properties,
+};
 
+use crate::bundle::BundleExport;
+use crate::claims::{Registry, Source};
+use crate::components::claims_card::ClaimsComponent;
 use crate::components::content_iframe::ContentIFrame;
+use crate::components::disclosure_stats::DisclosureStatsComponent;
+use crate::components::policy_results::PolicyResultsComponent;
+use crate::components::privacy_lint::PrivacyLintComponent;
 use crate::components::redacted_bytes_component::Direction;
 use crate::components::redacted_bytes_component::RedactedBytesComponent;
-
-const REDACTED_CHAR: char = 'X'; // '█' '🙈' 'X'
+use crate::components::request_checks::RequestChecksComponent;
+use crate::components::search_bar::SearchBar;
+use crate::export::download;
+use crate::highlight::{Highlights, CLAIM_SOURCE_SELECTOR, CURRENT_MATCH_SELECTOR};
+use crate::link::{ShareLink, PRACTICAL_URL_LEN};
+use crate::policy::Policy;
+use crate::redaction::RedactionStyle;
+use crate::report::Format;
+use crate::search::{find_matches, parse_query, Match, SearchMode};
+use crate::verify::{FileState, ProofReport, Step, SuppliedKey, VerificationError, VerifiedProof};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
+    pub id: usize,
     pub name: String,
-    pub file_type: String,
-    pub data: Vec<u8>,
-    pub pem: p256::PublicKey,
+    pub state: FileState,
+    pub redaction: RedactionStyle,
+    /// Stop loading or verifying the file
+    pub on_cancel: Callback<()>,
+    /// Remove the card
+    pub on_close: Callback<()>,
+    /// Link to the proof, once the user asked for it
+    #[prop_or_default]
+    pub share: Option<Result<ShareLink, String>>,
+    /// Create a link to the proof and copy it
+    pub on_share: Callback<()>,
+    /// Offline bundle of the proof, once the user asked for it
+    #[prop_or_default]
+    pub bundle: Option<BundleExport>,
+    /// Save an offline bundle of the proof
+    pub on_bundle: Callback<()>,
+    /// Rules to check the proof against
+    #[prop_or_default]
+    pub policy: Option<Rc<Policy>>,
+    /// Claim extractors of the known services
+    pub claims: Rc<Registry>,
+    /// The Notary key, when it came with the proof rather than from the user
+    #[prop_or_default]
+    pub supplied_key: Option<SuppliedKey>,
 }
 
-#[function_component]
-pub fn ViewFile(props: &Props) -> Html {
-    // Verify the session proof against the Notary's public key
-    fn verify_proof(session: &SessionProof, pem: p256::PublicKey) -> Result<(), String> {
-        session
-            .verify_with_default_cert_verifier(pem)
-            .map_err(|err| err.to_string())
-    }
+/// Element id of a proof card, to link to it from the dashboard
+pub fn card_id(id: usize) -> String {
+    format!("proof-{}", id)
+}
 
-    fn parse_tls_proof(json_str: &str, pem: p256::PublicKey) -> Html {
-        let tls_proof: Result<TlsProof, serde_json::Error> = serde_json::from_str(json_str);
+/// Matches of the search query in both transcripts, sent before received
+struct SearchResults {
+    matches: Vec<(Direction, Match)>,
+    /// Why the query is invalid
+    error: Option<String>,
+}
 
-        match tls_proof {
-            Err(e) => html! {
-                 <div>{format!("Parsing failed {}", e.to_string())}</div>
-            },
-            Ok(tls_proof) => {
-                let TlsProof {
-                    // The session proof establishes the identity of the server and the commitments
-                    // to the TLS transcript.
-                    session,
-                    // The substrings proof proves select portions of the transcript, while redacting
-                    // anything the Prover chose not to disclose.
-                    substrings,
-                } = tls_proof;
-
-                let proof_verification = verify_proof(&session, pem);
-
-                if proof_verification.is_err() {
-                    return html! {
-                        <>
-                            <div role="alert">
-                                <div class="bg-red-500 text-white font-bold rounded-t px-4 py-2">
-                                    {"Invalid Proof"}
-                                </div>
-                                <div class="border border-t-0 border-red-400 rounded-b bg-red-100 px-4 py-3 text-red-700">
-                                    { "❌ " }{proof_verification.unwrap_err().to_string()}
-                                </div>
-                            </div>
-                        </>
-                    };
-                }
+/// Search state of a proof card
+struct Search {
+    results: Rc<SearchResults>,
+    /// Index of the selected match, wrapped around the number of matches
+    current: isize,
+    on_search: Callback<(String, SearchMode)>,
+    on_step: Callback<isize>,
+}
 
-                let proof_verification_feedback = "✅ Proof successfully verified ✅".to_string();
+#[function_component]
+pub fn ViewFile(props: &Props) -> Html {
+    fn view_proof(
+        report: &Rc<ProofReport>,
+        proof: &VerifiedProof,
+        redaction: RedactionStyle,
+        search: &Search,
+        card_ref: &NodeRef,
+        // Policy results, claims, request checks and privacy findings, shown above the transcripts
+        panels: Html,
+        claim_source: Option<&Source>,
+    ) -> Html {
+        let proof_verification_feedback = "✅ Proof successfully verified ✅".to_string();
 
-                let SessionProof {
-                    // The session header that was signed by the Notary is a succinct commitment to the TLS transcript.
-                    header,
-                    // This is the server name, checked against the certificate chain shared in the TLS handshake.
-                    server_name,
-                    ..
-                } = session;
+        // The time at which the session was recorded
+        let time = chrono::DateTime::UNIX_EPOCH + Duration::from_secs(proof.time);
 
-                // The time at which the session was recorded
-                let time = chrono::DateTime::UNIX_EPOCH + Duration::from_secs(header.time());
+        let all_matches = &search.results.matches;
+        let current = (!all_matches.is_empty())
+            .then(|| search.current.rem_euclid(all_matches.len() as isize) as usize);
+        let current_status = current.map(|index| {
+            let (direction, m) = &all_matches[index];
+            let mut status = format!(
+                "Match {} of {} in {} bytes {}..{}",
+                index + 1,
+                all_matches.len(),
+                direction,
+                m.range.start,
+                m.range.end
+            );
+            if m.overlaps_redaction {
+                status.push_str(" ⚠️ overlaps a redacted range");
+            }
+            status
+        });
+        let highlights = |direction: Direction| Highlights {
+            matches: all_matches
+                .iter()
+                .filter(|(d, _)| *d == direction)
+                .map(|(_, m)| m.range.clone())
+                .collect(),
+            current: current
+                .map(|index| &all_matches[index])
+                .filter(|(d, _)| *d == direction)
+                .map(|(_, m)| m.range.clone()),
+            claim_sources: claim_source
+                .filter(|source| source.direction == direction)
+                .map(|source| source.ranges.clone())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
 
-                // Verify the substrings proof against the session header.
+        html! {
+            <div class="p-4 flex flex-col justify-center items-center w-full">
+                <div class="p-4 w-5/6">
+                    <b>{"Server domain:" }</b>
+                    <div class="bg-black text-white p-4 rounded-md">
+                        <pre>{proof.server_name.clone()}</pre>
+                    </div>
+                    <b>{"Notarization time:" }</b>
+                    <div class="bg-black text-white p-4 rounded-md">
+                        <pre>{time.to_string()}</pre>
+                    </div>
+                    <b>{"Proof:" }</b>
+                    <div class="bg-black text-white p-4 rounded-md">
+                        <pre>{proof_verification_feedback}</pre>
+                    </div>
+                </div>
 
-                // This returns the redacted transcripts
-                let (mut sent, mut recv) = substrings.verify(&header).unwrap();
+                {panels}
 
-                // Replace the bytes which the Prover chose not to disclose with 'X'
-                sent.set_redacted(b'X');
-                recv.set_redacted(b'X');
+                <DisclosureStatsComponent card_ref={card_ref.clone()} report={report.clone()} />
 
-                let redacted_ranges_send: Vec<Range<usize>> =
-                    sent.redacted().clone().iter_ranges().collect();
-                let redacted_ranges_recv: Vec<Range<usize>> =
-                    recv.redacted().clone().iter_ranges().collect();
+                <SearchBar
+                    matches={all_matches.len()}
+                    possible_matches={all_matches.iter().filter(|(_, m)| m.overlaps_redaction).count()}
+                    current={current_status}
+                    error={search.results.error.clone()}
+                    on_search={search.on_search.clone()}
+                    on_step={search.on_step.clone()}
+                />
 
-                html! {
-                    <div class="p-4 flex flex-col justify-center items-center w-full">
-                        <div class="p-4 w-5/6">
-                            <b>{"Server domain:" }</b>
-                            <div class="bg-black text-white p-4 rounded-md">
-                                <pre>{server_name.as_str().to_string()}</pre>
-                            </div>
-                            <b>{"Notarization time:" }</b>
-                            <div class="bg-black text-white p-4 rounded-md">
-                                <pre>{time.to_string()}</pre>
-                            </div>
-                            <b>{"Proof:" }</b>
-                            <div class="bg-black text-white p-4 rounded-md">
-                                <pre>{proof_verification_feedback}</pre>
-                            </div>
-                        </div>
+                <RedactedBytesComponent direction={Direction::Send} redaction={redaction} report={report.clone()} highlights={highlights(Direction::Send)} />
 
-                        <RedactedBytesComponent direction={Direction::Send} redacted_char={REDACTED_CHAR} bytes={sent.data().to_vec()} redacted_ranges={redacted_ranges_send} />
+                <ContentIFrame report={report.clone()} redaction={redaction} highlights={highlights(Direction::Received)} />
 
-                        <ContentIFrame bytes={recv.data().to_vec()} />
+                <RedactedBytesComponent direction={Direction::Received} redaction={redaction} report={report.clone()} highlights={highlights(Direction::Received)} />
 
-                        <RedactedBytesComponent direction={Direction::Received} redacted_char={REDACTED_CHAR} bytes={recv.data().to_vec()} redacted_ranges={redacted_ranges_recv} />
+            </div>
+        }
+    }
 
+    fn view_error(error: &VerificationError) -> Html {
+        match error {
+            VerificationError::Parse(_) => html! {
+                <div>{error.to_string()}</div>
+            },
+            VerificationError::WrongFile(artifact) => html! {
+                <div role="alert">
+                    <div class="bg-yellow-500 text-black font-bold rounded-t px-4 py-2">
+                        {format!("Not a proof: this is {}", artifact)}
                     </div>
+                    <div class="border border-t-0 border-yellow-400 rounded-b bg-yellow-100 px-4 py-3 text-yellow-900">
+                        {artifact.advice()}
+                    </div>
+                </div>
+            },
+            _ => html! {
+                <div role="alert">
+                    <div class="bg-red-500 text-white font-bold rounded-t px-4 py-2">
+                        {"Invalid Proof"}
+                    </div>
+                    <div class="border border-t-0 border-red-400 rounded-b bg-red-100 px-4 py-3 text-red-700">
+                        { "❌ " }{error.to_string()}
+                    </div>
+                </div>
+            },
+        }
+    }
+
+    fn view_supplied_key(key: &SuppliedKey) -> Html {
+        let (class, note) = if key.differs {
+            (
+                "text-red-400 font-bold",
+                " It is not the default Notary key.",
+            )
+        } else {
+            ("text-yellow-400", " It is the default Notary key.")
+        };
+        html! {
+            <p class={classes!("text-sm", "text-center", class)} role="alert">
+                {format!("⚠️ Verified with Notary key {} supplied by {}. Compare it with the key of the Notary you trust before relying on this proof.", key.fingerprint, key.source)}
+                {note}
+            </p>
+        }
+    }
+
+    fn view_share(share: &Result<ShareLink, String>) -> Html {
+        let share = match share {
+            Ok(share) => share,
+            Err(err) => {
+                return html! {
+                    <p class="text-red-400 text-sm">{err}</p>
                 }
             }
+        };
+        let copied = match &share.copied {
+            None => "Copying link...".to_string(),
+            Some(Ok(())) => "✅ Link copied".to_string(),
+            Some(Err(err)) => format!("Could not copy the link, copy it from the field: {}", err),
+        };
+
+        html! {
+            <div class="w-full flex flex-col gap-1 text-sm">
+                <input class="w-full p-2 bg-zinc-800 border border-gray-600 rounded font-mono" type="text" readonly={true} value={share.url.clone()} />
+                <p>{copied}</p>
+                {match &share.notary {
+                    Some(notary) => html! {
+                        <p class="text-yellow-400">{"The link proposes Notary key "}<span class="font-mono">{notary.clone()}</span>{", whoever opens it is asked before it is used"}</p>
+                    },
+                    None => html! {
+                        <p class="text-gray-400">{"The link has no Notary key, the proof is verified with the key of whoever opens it"}</p>
+                    },
+                }}
+                if share.is_too_long() {
+                    <p class="text-yellow-400">
+                        {format!(
+                            "⚠️ The link is {} characters long. Links over {} characters may be cut off by browsers, chat apps or mail clients, share the proof file instead.",
+                            share.url.len(),
+                            PRACTICAL_URL_LEN
+                        )}
+                    </p>
+                }
+            </div>
         }
     }
 
-    let json_str = str::from_utf8(&props.data).unwrap();
+    let search_input = use_state(|| (String::new(), SearchMode::default()));
+    let current_match = use_state(|| 0isize);
+    let card_ref = use_node_ref();
+    let selected_claim = use_state(|| None::<usize>);
+
+    // Bring the selected match into view
+    {
+        let card_ref = card_ref.clone();
+        use_effect_with(((*search_input).clone(), *current_match), move |_| {
+            let current = card_ref
+                .cast::<Element>()
+                .and_then(|card| card.query_selector(CURRENT_MATCH_SELECTOR).ok().flatten());
+            if let Some(current) = current {
+                current.scroll_into_view();
+            }
+        });
+    }
+
+    // Bring the bytes of the selected claim into view
+    {
+        let card_ref = card_ref.clone();
+        use_effect_with(*selected_claim, move |selected| {
+            let source = selected.and_then(|_| {
+                card_ref
+                    .cast::<Element>()?
+                    .query_selector(CLAIM_SOURCE_SELECTOR)
+                    .ok()
+                    .flatten()
+            });
+            if let Some(source) = source {
+                source.scroll_into_view();
+            }
+        });
+    }
+
+    let report = match &props.state {
+        FileState::Done(report) => Some(report.clone()),
+        _ => None,
+    };
+
+    // Search both transcripts, sent before received, again only when the proof or query changes
+    let search_results = use_memo(
+        (report.clone(), (*search_input).clone()),
+        |(report, (query, mode))| {
+            let (pattern, error) = match parse_query(query, *mode) {
+                Ok(pattern) => (pattern, None),
+                Err(err) => (Vec::new(), Some(err)),
+            };
+            let proof = report
+                .as_ref()
+                .and_then(|report| report.result.as_ref().ok());
+            let matches = [Direction::Send, Direction::Received]
+                .into_iter()
+                .filter_map(|direction| Some((proof?.transcript(&direction), direction)))
+                .flat_map(|(transcript, direction)| {
+                    find_matches(&transcript.bytes, &transcript.redacted, &pattern, *mode)
+                        .into_iter()
+                        .map(move |m| (direction.clone(), m))
+                })
+                .collect();
+            SearchResults { matches, error }
+        },
+    );
+
+    // Evaluated again only when the proof or the policy changes, so a freshness rule is checked
+    // against the time of that render
+    let policy_results = use_memo(
+        (report.clone(), props.policy.clone()),
+        |(report, policy)| {
+            let report = report.as_ref()?;
+            let proof = report.result.as_ref().ok()?;
+            let now = (js_sys::Date::now() / 1000.0) as u64;
+            Some(policy.as_ref()?.evaluate(proof, &report.notary, now))
+        },
+    );
+
+    let claims = use_memo((report, props.claims.clone()), |(report, registry)| {
+        registry.claims(report.as_ref()?.result.as_ref().ok()?)
+    });
+
+    let search = Search {
+        results: search_results,
+        current: *current_match,
+        on_search: {
+            let search_input = search_input.clone();
+            let current_match = current_match.clone();
+            Callback::from(move |input| {
+                search_input.set(input);
+                current_match.set(0);
+            })
+        },
+        on_step: {
+            let current_match = current_match.clone();
+            Callback::from(move |step| current_match.set(*current_match + step))
+        },
+    };
+
+    // Save a report of the verification
+    let export = |format: Format| {
+        let name = props.name.clone();
+        let state = props.state.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let FileState::Done(report) = &state {
+                let content = format.render(&name, report);
+                download(&format.file_name(&name), format.mime_type(), &content);
+            }
+        })
+    };
+
     html! {
-            <div class="p-4 flex flex-col justify-center items-center bg-zinc-700 border border-white border-dashed rounded-2xl">
-                <p class="text-center">{ format!("{}", &props.name) }</p>
+            <div id={card_id(props.id)} ref={card_ref.clone()} class="p-4 flex flex-col justify-center items-center bg-zinc-700 border border-white border-dashed rounded-2xl">
+                <div class="flex w-full items-center">
+                    <div class="flex-1"></div>
+                    <p class="text-center">{ format!("{}", &props.name) }</p>
+                    <div class="flex-1 flex justify-end gap-2">
+                        if matches!(props.state, FileState::Done(_)) {
+                            <span class="text-sm text-gray-400">{"Report:"}</span>
+                            {for Format::ALL.into_iter().map(|format| html! {
+                                <button class="px-2 hover:bg-black rounded" title="Save a report of the verification" onclick={export(format)}>{format.label()}</button>
+                            })}
+                            <button class="px-2 hover:bg-black rounded" title="Save one HTML file with the proof, the Notary key and the verifier, which verifies the proof again when opened offline" onclick={props.on_bundle.reform(|_| ())} disabled={props.bundle == Some(BundleExport::Building)}>{"Offline bundle"}</button>
+                        }
+                        if matches!(&props.state, FileState::Done(report) if report.result.is_ok()) {
+                            <button class="px-2 hover:bg-black rounded" title="Copy a link that opens and verifies this proof" onclick={props.on_share.reform(|_| ())}>{"Share"}</button>
+                        }
+                        if props.state.is_pending() {
+                            <button class="px-2 hover:bg-black rounded" onclick={props.on_cancel.reform(|_| ())}>{"Cancel"}</button>
+                        }
+                        <button class="px-2 hover:bg-black rounded" title="Close" onclick={props.on_close.reform(|_| ())}>{"✕"}</button>
+                    </div>
+                </div>
+
+                if let (Some(key), FileState::Done(_)) = (&props.supplied_key, &props.state) {
+                    {view_supplied_key(key)}
+                }
+
+                if let Some(share) = &props.share {
+                    {view_share(share)}
+                }
+
+                {match &props.bundle {
+                    Some(BundleExport::Building) => html! { <p class="text-sm">{"⏳ Building the offline bundle..."}</p> },
+                    Some(BundleExport::Saved) => html! { <p class="text-sm">{"✅ Offline bundle saved, it verifies the proof again when opened, without network access"}</p> },
+                    Some(BundleExport::Failed(err)) => html! { <p class="text-sm text-red-400">{format!("Saving the offline bundle failed: {}", err)}</p> },
+                    None => html! {},
+                }}
 
                 <div class="flex-1 flex flex-col justify-center p-4">
                     <div class="container mx-auto px-4">
-                    if props.file_type.contains("application/json") {
-                        {parse_tls_proof(json_str, props.pem)}
-                    }
+                    {match &props.state {
+                        FileState::Loading => html! { <p>{"⏳ Reading file..."}</p> },
+                        FileState::Queued => html! { <p>{"⏳ Waiting for other proofs to be verified..."}</p> },
+                        FileState::Verifying(step) => html! {
+                            <div class="flex flex-col items-center gap-2">
+                                <p>{format!("⏳ Verifying proof ({}/{}): {}...", step.number(), Step::COUNT, step)}</p>
+                                <progress class="w-64" max={Step::COUNT.to_string()} value={(step.number() - 1).to_string()}></progress>
+                            </div>
+                        },
+                        FileState::Done(report) => match &report.result {
+                            Ok(proof) => {
+                                let policy = match (&props.policy, &*policy_results) {
+                                    (Some(policy), Some(results)) => html! {
+                                        <PolicyResultsComponent name={policy.name.clone()} results={results.clone()} />
+                                    },
+                                    _ => html! {},
+                                };
+                                let claims = (*claims).as_ref();
+                                let claim_source = claims.and_then(|(_, claims)| {
+                                    Some(&claims.get((*selected_claim)?)?.as_ref().ok()?.source)
+                                });
+                                let claims_card = match claims {
+                                    Some((service, claims)) => {
+                                        let selected = selected_claim.clone();
+                                        html! {
+                                            <ClaimsComponent service={service.clone()} claims={claims.clone()} selected={*selected_claim} on_select={Callback::from(move |index| selected.set(index))} />
+                                        }
+                                    }
+                                    None => html! {},
+                                };
+                                let panels = html! {
+                                    <>
+                                        {policy}
+                                        {claims_card}
+                                        <RequestChecksComponent report={report.clone()} />
+                                        <PrivacyLintComponent report={report.clone()} />
+                                    </>
+                                };
+                                view_proof(report, proof, props.redaction, &search, &card_ref, panels, claim_source)
+                            }
+                            Err(error) => view_error(error),
+                        },
+                        FileState::Failed(error) => html! { <div>{error}</div> },
+                        FileState::Cancelled => html! { <p>{"Verification cancelled"}</p> },
+                    }}
                     </div>
                 </div>
             </div>
@@ -0,0 +1,50 @@
+use gloo::file::{Blob, ObjectUrl};
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlAnchorElement;
+
+/// Quote a CSV field when it contains a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Build a CSV document with a header line
+pub fn csv(header: &[&str], rows: &[Vec<String>]) -> String {
+    let line = |fields: Vec<String>| {
+        fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\r\n"
+    };
+
+    let mut csv = line(header.iter().map(|field| field.to_string()).collect());
+    for row in rows {
+        csv.push_str(&line(row.clone()));
+    }
+    csv
+}
+
+/// Let the browser save `content` as a file
+pub fn download(file_name: &str, mime_type: &str, content: &str) {
+    let blob = Blob::new_with_options(content, Some(mime_type));
+    let url = ObjectUrl::from(blob);
+
+    let anchor = gloo::utils::document()
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+    if let Some(anchor) = anchor {
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+    }
+
+    // Revoke the URL once the download has started
+    Timeout::new(0, move || drop(url)).forget();
+}
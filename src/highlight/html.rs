@@ -0,0 +1,239 @@
+use super::{fill, starts_with, TokenKind};
+use crate::redaction::MarkedChar;
+
+/// Tokenize HTML, or XML when `html` is false (XML has no raw text `<script>` and `<style>`).
+pub fn tokenize(chars: &[MarkedChar], html: bool) -> Vec<Option<TokenKind>> {
+    let mut kinds = vec![None; chars.len()];
+
+    // Position right after the first occurrence of `pattern` at or after `pos`
+    let skip_past = |pos: usize, pattern: &str| {
+        (pos..chars.len())
+            .find(|&i| starts_with(chars, i, pattern))
+            .map_or(chars.len(), |i| i + pattern.chars().count())
+    };
+    let is_name_char = |&MarkedChar { c, redacted, .. }: &MarkedChar| {
+        redacted || c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let MarkedChar { c, redacted, .. } = chars[i];
+        if redacted {
+            i += 1;
+            continue;
+        }
+
+        if starts_with(chars, i, "<!--") {
+            i = skip_past(i + 4, "-->");
+            fill(&mut kinds, start, i, TokenKind::Comment);
+        } else if starts_with(chars, i, "<!") || starts_with(chars, i, "<?") {
+            i = skip_past(i + 2, ">");
+            fill(&mut kinds, start, i, TokenKind::Doctype);
+        } else if c == '<'
+            && matches!(chars.get(i + 1), Some(next) if !next.redacted && (next.c.is_alphabetic() || next.c == '/'))
+        {
+            i += 1;
+            let closing = chars[i].c == '/';
+            if closing {
+                i += 1;
+            }
+            fill(&mut kinds, start, i, TokenKind::Punctuation);
+
+            let name_start = i;
+            while chars.get(i).map_or(false, is_name_char) {
+                i += 1;
+            }
+            fill(&mut kinds, name_start, i, TokenKind::Tag);
+            let name: String = chars[name_start..i].iter().map(|c| c.c).collect();
+
+            // Attributes up to the end of the tag
+            while let Some(&MarkedChar { c, redacted, .. }) = chars.get(i) {
+                let attr_start = i;
+                match c {
+                    _ if redacted => i += 1,
+                    '>' => {
+                        kinds[i] = Some(TokenKind::Punctuation);
+                        i += 1;
+                        break;
+                    }
+                    '/' | '=' => {
+                        kinds[i] = Some(TokenKind::Punctuation);
+                        i += 1;
+                    }
+                    '"' | '\'' => {
+                        i = skip_past(i + 1, &c.to_string());
+                        fill(&mut kinds, attr_start, i, TokenKind::AttrValue);
+                    }
+                    c if c.is_whitespace() => i += 1,
+                    _ => {
+                        // An attribute name, or an unquoted value right after an `=`
+                        let after_equals = matches!(
+                            chars[..i].iter().rev().find(|c| !c.c.is_whitespace()),
+                            Some(MarkedChar {
+                                c: '=',
+                                redacted: false,
+                                ..
+                            })
+                        );
+                        while let Some(&MarkedChar { c, redacted, .. }) = chars.get(i) {
+                            if !redacted && (c.is_whitespace() || matches!(c, '>' | '=' | '/')) {
+                                break;
+                            }
+                            i += 1;
+                        }
+                        let kind = if after_equals {
+                            TokenKind::AttrValue
+                        } else {
+                            TokenKind::AttrName
+                        };
+                        fill(&mut kinds, attr_start, i, kind);
+                    }
+                }
+            }
+
+            // The content of scripts and style sheets is not markup
+            let name = name.to_ascii_lowercase();
+            if html && !closing && (name == "script" || name == "style") {
+                let end = format!("</{}", name);
+                i = (i..chars.len())
+                    .find(|&i| starts_with(chars, i, &end))
+                    .unwrap_or(chars.len());
+            }
+        } else if c == '&' {
+            i += 1;
+            while let Some(&MarkedChar {
+                c, redacted: false, ..
+            }) = chars.get(i)
+            {
+                if !(c.is_ascii_alphanumeric() || c == '#') || i - start > 32 {
+                    break;
+                }
+                i += 1;
+            }
+            if matches!(
+                chars.get(i),
+                Some(MarkedChar {
+                    c: ';',
+                    redacted: false,
+                    ..
+                })
+            ) {
+                i += 1;
+                fill(&mut kinds, start, i, TokenKind::Entity);
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::highlight::tests::{chars, tokens};
+    use crate::highlight::{Language, TokenKind::*};
+
+    #[test]
+    fn markup() {
+        let chars = chars(
+            r#"<!DOCTYPE html><!-- c --><p class="a" id=b>x &amp; y</p>"#,
+            &[],
+        );
+        assert_eq!(
+            tokens(&chars, Language::Html),
+            vec![
+                (0..15, Doctype),
+                (15..25, Comment),
+                (25..26, Punctuation),
+                (26..27, Tag),
+                (28..33, AttrName),
+                (33..34, Punctuation),
+                (34..37, AttrValue),
+                (38..40, AttrName),
+                (40..41, Punctuation),
+                (41..42, AttrValue),
+                (42..43, Punctuation),
+                (45..50, Entity),
+                (52..54, Punctuation),
+                (54..55, Tag),
+                (55..56, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn script_content_is_not_markup() {
+        let chars_ = chars("<script>a<b</script>", &[]);
+        assert_eq!(
+            tokens(&chars_, Language::Html),
+            vec![
+                (0..1, Punctuation),
+                (1..7, Tag),
+                (7..8, Punctuation),
+                (11..13, Punctuation),
+                (13..19, Tag),
+                (19..20, Punctuation),
+            ]
+        );
+
+        let chars_ = chars(r#"<?xml version="1.0"?><a/>"#, &[]);
+        assert_eq!(
+            tokens(&chars_, Language::Xml),
+            vec![
+                (0..21, Doctype),
+                (21..22, Punctuation),
+                (22..23, Tag),
+                (23..25, Punctuation)
+            ]
+        );
+    }
+
+    #[test]
+    fn tokens_cut_off_at_a_redaction() {
+        // The closing quote is redacted, the value runs to the end
+        let chars_ = chars(r#"<a href="secret">x</a>"#, &["secret\">x</a>"]);
+        assert_eq!(
+            tokens(&chars_, Language::Html),
+            vec![
+                (0..1, Punctuation),
+                (1..2, Tag),
+                (3..7, AttrName),
+                (7..8, Punctuation),
+                (8..22, AttrValue),
+            ]
+        );
+
+        // Redacted characters are part of the tag name
+        let chars_ = chars("<div>", &["iv"]);
+        assert_eq!(
+            tokens(&chars_, Language::Html),
+            vec![(0..1, Punctuation), (1..4, Tag), (4..5, Punctuation)]
+        );
+
+        // An entity needs its semicolon
+        assert!(tokens(&chars("a &amp; b", &["mp; b"]), Language::Html).is_empty());
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let chars = chars(r#"<p title="café">ü &eacute;</p>"#, &[]);
+        assert_eq!(
+            tokens(&chars, Language::Html),
+            vec![
+                (0..1, Punctuation),
+                (1..2, Tag),
+                (3..8, AttrName),
+                (8..9, Punctuation),
+                (9..15, AttrValue),
+                (15..16, Punctuation),
+                (18..26, Entity),
+                (26..28, Punctuation),
+                (28..29, Tag),
+                (29..30, Punctuation),
+            ]
+        );
+        assert_eq!(chars[18].offset, Some(20));
+    }
+}
@@ -0,0 +1,160 @@
+use super::{fill, starts_with, tokenize as tokenize_body, Language, TokenKind};
+use crate::redaction::MarkedChar;
+
+pub fn tokenize(chars: &[MarkedChar]) -> Vec<Option<TokenKind>> {
+    let mut kinds = vec![None; chars.len()];
+
+    let head_end = (0..chars.len())
+        .find(|&i| starts_with(chars, i, "\r\n\r\n"))
+        .map_or(chars.len(), |i| i + 4);
+
+    let mut language = Language::Plain;
+    let mut line_start = 0;
+    while line_start < head_end {
+        let line_end = (line_start..head_end)
+            .find(|&i| chars[i].c == '\n' && !chars[i].redacted)
+            .map_or(head_end, |i| i + 1);
+        let line = &chars[line_start..line_end];
+        let text: String = line.iter().map(|c| c.c).collect();
+        let text = text.trim_end();
+
+        if line_start == 0 {
+            // Request line (`GET /path HTTP/1.1`) or status line (`HTTP/1.1 200 OK`)
+            let mut offset = line_start;
+            for (n, part) in text.split(' ').enumerate() {
+                let len = part.chars().count();
+                let kind = match (text.starts_with("HTTP/"), n) {
+                    (true, 0) => Some(TokenKind::Keyword),
+                    (true, 1) => Some(TokenKind::Number),
+                    (true, _) => Some(TokenKind::String),
+                    (false, 0) => Some(TokenKind::Keyword),
+                    (false, 1) => Some(TokenKind::Url),
+                    (false, 2) => Some(TokenKind::Keyword),
+                    (false, _) => None,
+                };
+                if let Some(kind) = kind {
+                    fill(&mut kinds, offset, offset + len, kind);
+                }
+                offset += len + 1;
+            }
+        } else if let Some(colon) = line.iter().position(|c| c.c == ':' && !c.redacted) {
+            let colon = line_start + colon;
+            fill(&mut kinds, line_start, colon, TokenKind::Property);
+            kinds[colon] = Some(TokenKind::Punctuation);
+            fill(
+                &mut kinds,
+                colon + 1,
+                line_start + text.chars().count(),
+                TokenKind::AttrValue,
+            );
+
+            let (name, value) = text.split_at(text.find(':').unwrap_or(0));
+            if name.trim().eq_ignore_ascii_case("content-type") {
+                language = Language::from_content_type(value);
+            }
+        }
+
+        line_start = line_end;
+    }
+
+    // Highlight the body according to its content type
+    for (slot, kind) in kinds[head_end..]
+        .iter_mut()
+        .zip(tokenize_body(&chars[head_end..], language))
+    {
+        *slot = kind;
+    }
+
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::highlight::tests::{chars, tokens};
+    use crate::highlight::{Language, TokenKind::*};
+
+    #[test]
+    fn request() {
+        let chars = chars("GET /path HTTP/1.1\r\nHost: a.com\r\n\r\n", &[]);
+        assert_eq!(
+            tokens(&chars, Language::Http),
+            vec![
+                (0..3, Keyword),
+                (4..9, Url),
+                (10..18, Keyword),
+                (20..24, Property),
+                (24..25, Punctuation),
+                (25..31, AttrValue),
+            ]
+        );
+    }
+
+    #[test]
+    fn response_body_in_its_content_type() {
+        let chars = chars(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"a\": true}",
+            &[],
+        );
+        assert_eq!(
+            tokens(&chars, Language::Http),
+            vec![
+                (0..8, Keyword),
+                (9..12, Number),
+                (13..15, String),
+                (17..29, Property),
+                (29..30, Punctuation),
+                (30..47, AttrValue),
+                (51..52, Punctuation),
+                (52..55, Property),
+                (55..56, Punctuation),
+                (57..61, Boolean),
+                (61..62, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn redacted_values() {
+        let chars_ = chars(
+            "GET /secret HTTP/1.1\r\nCookie: secret\r\n\r\n",
+            &["secret", "ie: secret"],
+        );
+        assert_eq!(
+            tokens(&chars_, Language::Http),
+            vec![(0..3, Keyword), (4..11, Url), (12..20, Keyword)]
+        );
+
+        let chars_ = chars("GET / HTTP/1.1\r\nCookie: secret\r\n\r\n", &["secret"]);
+        assert_eq!(
+            tokens(&chars_, Language::Http),
+            vec![
+                (0..3, Keyword),
+                (4..5, Url),
+                (6..14, Keyword),
+                (16..22, Property),
+                (22..23, Punctuation),
+                (23..30, AttrValue),
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let chars = chars("GET / HTTP/1.1\r\nName: Zoë\r\nA: b\r\n\r\n", &[]);
+        assert_eq!(
+            tokens(&chars, Language::Http),
+            vec![
+                (0..3, Keyword),
+                (4..5, Url),
+                (6..14, Keyword),
+                (16..20, Property),
+                (20..21, Punctuation),
+                (21..25, AttrValue),
+                (27..28, Property),
+                (28..29, Punctuation),
+                (29..31, AttrValue),
+            ]
+        );
+        assert_eq!(chars[27].offset, Some(28));
+    }
+}
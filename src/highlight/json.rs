@@ -0,0 +1,196 @@
+use super::{fill, TokenKind};
+use crate::redaction::MarkedChar;
+
+pub fn tokenize(chars: &[MarkedChar]) -> Vec<Option<TokenKind>> {
+    let mut kinds = vec![None; chars.len()];
+
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let MarkedChar { c, redacted, .. } = chars[i];
+        i += 1;
+        if redacted {
+            continue;
+        }
+
+        match c {
+            '"' => {
+                let mut escaped = false;
+                while let Some(&MarkedChar { c, redacted, .. }) = chars.get(i) {
+                    i += 1;
+                    if redacted {
+                        continue;
+                    }
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+
+                // A string followed by a colon is an object key
+                let is_key = chars[i..]
+                    .iter()
+                    .find(|c| c.redacted || !c.c.is_whitespace())
+                    .map_or(false, |c| !c.redacted && c.c == ':');
+                let kind = if is_key {
+                    TokenKind::Property
+                } else {
+                    TokenKind::String
+                };
+                fill(&mut kinds, start, i, kind);
+            }
+            '{' | '}' | '[' | ']' | ',' | ':' => kinds[start] = Some(TokenKind::Punctuation),
+            '-' | '0'..='9' => {
+                while let Some(&MarkedChar {
+                    c, redacted: false, ..
+                }) = chars.get(i)
+                {
+                    if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+                        break;
+                    }
+                    i += 1;
+                }
+                fill(&mut kinds, start, i, TokenKind::Number);
+            }
+            c if c.is_ascii_alphabetic() => {
+                while let Some(&MarkedChar {
+                    c, redacted: false, ..
+                }) = chars.get(i)
+                {
+                    if !c.is_ascii_alphabetic() {
+                        break;
+                    }
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().map(|c| c.c).collect();
+                match word.as_str() {
+                    "true" | "false" => fill(&mut kinds, start, i, TokenKind::Boolean),
+                    "null" => fill(&mut kinds, start, i, TokenKind::Keyword),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::highlight::tests::{chars, tokens};
+    use crate::highlight::{Language, TokenKind::*};
+
+    #[test]
+    fn tokens_of_values() {
+        let chars = chars(r#"{"a": 1.5e3, "b": [true, null, "x"]}"#, &[]);
+        assert_eq!(
+            tokens(&chars, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..4, Property),
+                (4..5, Punctuation),
+                (6..11, Number),
+                (11..12, Punctuation),
+                (13..16, Property),
+                (16..17, Punctuation),
+                (18..19, Punctuation),
+                (19..23, Boolean),
+                (23..24, Punctuation),
+                (25..29, Keyword),
+                (29..30, Punctuation),
+                (31..34, String),
+                (34..36, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        let chars = chars(r#"["a\"b", 1]"#, &[]);
+        assert_eq!(
+            tokens(&chars, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..7, String),
+                (7..8, Punctuation),
+                (9..10, Number),
+                (10..11, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn redacted_characters_take_the_kind_of_their_token() {
+        let chars = chars(r#"{"a": "hunter2"}"#, &["hunter2"]);
+        assert_eq!(
+            tokens(&chars, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..4, Property),
+                (4..5, Punctuation),
+                (6..15, String),
+                (15..16, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokens_cut_off_at_a_redaction() {
+        // The string never ends, so it is not known to be a key
+        let chars_ = chars(r#"{"a": "hunter2"}"#, &["hunter2\"}"]);
+        assert_eq!(
+            tokens(&chars_, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..4, Property),
+                (4..5, Punctuation),
+                (6..16, String)
+            ]
+        );
+        let chars_ = chars(r#"{"name": 1}"#, &["\": 1}"]);
+        assert_eq!(
+            tokens(&chars_, Language::Json),
+            vec![(0..1, Punctuation), (1..11, String)]
+        );
+
+        // A redacted character ends a number or a word
+        let chars_ = chars(r#"{"n": 12345, "t": true}"#, &["345", "ue"]);
+        assert_eq!(
+            tokens(&chars_, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..4, Property),
+                (4..5, Punctuation),
+                (6..8, Number),
+                (11..12, Punctuation),
+                (13..16, Property),
+                (16..17, Punctuation),
+                (22..23, Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let chars = chars(r#"{"é": "ü", "n": 1}"#, &[]);
+        assert_eq!(
+            tokens(&chars, Language::Json),
+            vec![
+                (0..1, Punctuation),
+                (1..4, Property),
+                (4..5, Punctuation),
+                (6..9, String),
+                (9..10, Punctuation),
+                (11..14, Property),
+                (14..15, Punctuation),
+                (16..17, Number),
+                (17..18, Punctuation),
+            ]
+        );
+        // Tokens are counted in characters, the characters keep their byte offsets
+        assert_eq!(chars[16].offset, Some(18));
+    }
+}
@@ -0,0 +1,315 @@
+use std::ops::Range;
+
+use yew::prelude::*;
+
+use crate::components::redacted_bytes_component::redacted_span;
+use crate::redaction::{MarkedChar, RedactionStyle};
+
+mod html;
+mod http;
+mod json;
+
+/// Languages understood by the highlighter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Language {
+    Http,
+    Json,
+    Html,
+    Xml,
+    Plain,
+}
+
+impl Language {
+    /// Language of a message body with the given `Content-Type` header value
+    pub fn from_content_type(content_type: &str) -> Self {
+        let content_type = content_type.to_ascii_lowercase();
+        match content_type {
+            s if s.contains("html") => Language::Html,
+            s if s.contains("json") => Language::Json,
+            s if s.contains("xml") => Language::Xml,
+            _ => Language::Plain,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Keyword,
+    Url,
+    Number,
+    String,
+    Property,
+    Punctuation,
+    Boolean,
+    Tag,
+    AttrName,
+    AttrValue,
+    Comment,
+    Doctype,
+    Entity,
+}
+
+impl TokenKind {
+    /// CSS classes of the token, see `styles.css`
+    fn class(self) -> &'static str {
+        match self {
+            TokenKind::Keyword => "token keyword",
+            TokenKind::Url => "token url",
+            TokenKind::Number => "token number",
+            TokenKind::String => "token string",
+            TokenKind::Property => "token property",
+            TokenKind::Punctuation => "token punctuation",
+            TokenKind::Boolean => "token boolean",
+            TokenKind::Tag => "token tag",
+            TokenKind::AttrName => "token attr-name",
+            TokenKind::AttrValue => "token attr-value",
+            TokenKind::Comment => "token comment",
+            TokenKind::Doctype => "token doctype",
+            TokenKind::Entity => "token entity",
+        }
+    }
+}
+
+/// Transcript byte ranges to mark on top of the syntax highlighting
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Highlights {
+    /// Sorted, non-overlapping search matches
+    pub matches: Vec<Range<usize>>,
+    /// The match that is currently selected
+    pub current: Option<Range<usize>>,
+    /// The bytes that support the selected claim
+    pub claim_sources: Vec<Range<usize>>,
+    /// Sorted redacted ranges, to label the redacted characters with their range
+    pub redacted_ranges: Vec<Range<usize>>,
+    /// The redacted range that is being inspected
+    pub selected_redaction: Option<Range<usize>>,
+    /// Called with the redacted range when redacted characters are clicked
+    pub on_select_redaction: Option<Callback<Range<usize>>>,
+}
+
+/// Selector of the elements of the currently selected match, to scroll them into view
+pub const CURRENT_MATCH_SELECTOR: &str = ".search-current";
+/// Selector of the elements of the bytes that support the selected claim
+pub const CLAIM_SOURCE_SELECTOR: &str = ".claim-source";
+
+impl Highlights {
+    fn class(&self, offset: Option<usize>) -> Option<&'static str> {
+        let offset = offset?;
+        if self
+            .current
+            .as_ref()
+            .map_or(false, |current| current.contains(&offset))
+        {
+            return Some("search-current bg-yellow-500 text-black");
+        }
+        if self
+            .claim_sources
+            .iter()
+            .any(|source| source.contains(&offset))
+        {
+            return Some("claim-source bg-green-700");
+        }
+        let index = self.matches.partition_point(|range| range.end <= offset);
+        self.matches
+            .get(index)
+            .filter(|range| range.contains(&offset))
+            .map(|_| "bg-yellow-800")
+    }
+
+    fn redaction(&self, offset: Option<usize>) -> Option<Range<usize>> {
+        let offset = offset?;
+        let index = self
+            .redacted_ranges
+            .partition_point(|range| range.end <= offset);
+        self.redacted_ranges
+            .get(index)
+            .filter(|range| range.contains(&offset))
+            .cloned()
+    }
+
+    /// Redacted characters, labeled with their range when it is known
+    fn redacted_html(&self, text: String, offset: Option<usize>, style: &RedactionStyle) -> Html {
+        let Some(range) = self.redaction(offset) else {
+            return redacted_span(text, style);
+        };
+
+        let selected = self.selected_redaction.as_ref() == Some(&range);
+        let onclick = self.on_select_redaction.clone().map(|callback| {
+            let range = range.clone();
+            Callback::from(move |_: MouseEvent| callback.emit(range.clone()))
+        });
+
+        html! {
+            <span
+                class={classes!(self.on_select_redaction.is_some().then_some("cursor-pointer"), selected.then_some("outline outline-1 outline-red-500"))}
+                data-redaction={range.start.to_string()}
+                title={format!("Redacted bytes {}..{} ({} bytes)", range.start, range.end, range.len())}
+                {onclick}
+            >
+                {redacted_span(text, style)}
+            </span>
+        }
+    }
+}
+
+/// Selector of the first element of the redacted range starting at `start`
+pub fn redaction_selector(start: usize) -> String {
+    format!("[data-redaction=\"{}\"]", start)
+}
+
+/// Token kind of every character, `None` for plain text.
+///
+/// The tokenizers take the redaction flag of every character into account: a redacted
+/// character never starts or ends a token, it takes the kind of the token it is part of.
+pub fn tokenize(chars: &[MarkedChar], language: Language) -> Vec<Option<TokenKind>> {
+    match language {
+        Language::Http => http::tokenize(chars),
+        Language::Json => json::tokenize(chars),
+        Language::Html => html::tokenize(chars, true),
+        Language::Xml => html::tokenize(chars, false),
+        Language::Plain => vec![None; chars.len()],
+    }
+}
+
+/// Render highlighted text, with the redacted characters styled like in `RedactedBytesComponent`
+pub fn highlight(
+    chars: &[MarkedChar],
+    language: Language,
+    highlights: &Highlights,
+    redaction: &RedactionStyle,
+) -> Html {
+    let kinds = tokenize(chars, language);
+
+    struct Span {
+        kind: Option<TokenKind>,
+        mark: Option<&'static str>,
+        redacted: bool,
+        offset: Option<usize>,
+        text: String,
+    }
+
+    let mut spans: Vec<Span> = Vec::new();
+    for (
+        &MarkedChar {
+            c,
+            offset,
+            redacted,
+        },
+        kind,
+    ) in chars.iter().zip(kinds)
+    {
+        let mark = highlights.class(offset);
+        match spans.last_mut() {
+            Some(span) if span.kind == kind && span.mark == mark && span.redacted == redacted => {
+                span.text.push(c)
+            }
+            _ => spans.push(Span {
+                kind,
+                mark,
+                redacted,
+                offset,
+                text: c.to_string(),
+            }),
+        }
+    }
+
+    html! {
+        <>
+            { for spans.into_iter().map(|Span { kind, mark, redacted, offset, text }| {
+                let text = if redacted {
+                    highlights.redacted_html(text, offset, redaction)
+                } else {
+                    Html::from(text)
+                };
+                match (kind, mark) {
+                    (None, None) => text,
+                    (kind, mark) => html! {
+                        <span class={classes!(kind.map(TokenKind::class), mark)}>{text}</span>
+                    },
+                }
+            }) }
+        </>
+    }
+}
+
+/// Whether the unredacted characters at `pos` spell `pattern` (ignoring ASCII case)
+fn starts_with(chars: &[MarkedChar], pos: usize, pattern: &str) -> bool {
+    let mut chars = chars[pos.min(chars.len())..].iter();
+    pattern.chars().all(|expected| {
+        matches!(chars.next(), Some(&MarkedChar { c, redacted: false, .. }) if c.eq_ignore_ascii_case(&expected))
+    })
+}
+
+/// Set the kind of the characters in `start..end`
+fn fill(kinds: &mut [Option<TokenKind>], start: usize, end: usize, kind: TokenKind) {
+    for slot in &mut kinds[start..end] {
+        *slot = Some(kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charset::TranscriptDecoder;
+    use crate::redaction::marked_chars;
+    use crate::verify::Transcript;
+
+    /// The characters of `text`, with the parts equal to `redacted` redacted
+    pub(super) fn chars(text: &str, redacted: &[&str]) -> Vec<MarkedChar> {
+        let transcript = Transcript::redacting(text, redacted);
+        let bytes = &transcript.bytes;
+        marked_chars(
+            bytes,
+            &TranscriptDecoder::new(bytes),
+            0..bytes.len(),
+            &transcript.redacted,
+            &RedactionStyle::default(),
+        )
+    }
+
+    /// The tokens as character index ranges, with adjacent tokens of the same kind merged
+    pub(super) fn tokens(
+        chars: &[MarkedChar],
+        language: Language,
+    ) -> Vec<(Range<usize>, TokenKind)> {
+        let mut tokens: Vec<(Range<usize>, TokenKind)> = Vec::new();
+        for (i, kind) in tokenize(chars, language).into_iter().enumerate() {
+            let Some(kind) = kind else { continue };
+            match tokens.last_mut() {
+                Some((range, last)) if range.end == i && *last == kind => range.end += 1,
+                _ => tokens.push((i..i + 1, kind)),
+            }
+        }
+        tokens
+    }
+
+    #[test]
+    fn language_of_content_type() {
+        assert_eq!(
+            Language::from_content_type("text/HTML; charset=utf-8"),
+            Language::Html
+        );
+        assert_eq!(
+            Language::from_content_type("application/problem+json"),
+            Language::Json
+        );
+        assert_eq!(
+            Language::from_content_type("application/xml"),
+            Language::Xml
+        );
+        assert_eq!(Language::from_content_type("text/plain"), Language::Plain);
+    }
+
+    #[test]
+    fn plain_text_has_no_tokens() {
+        assert!(tokens(&chars("<p>1</p>", &[]), Language::Plain).is_empty());
+    }
+
+    #[test]
+    fn starts_with_skips_redacted_characters() {
+        let chars = chars("</script>", &["ipt"]);
+        assert!(starts_with(&chars, 0, "</SCR"));
+        assert!(!starts_with(&chars, 0, "</script"));
+        assert!(!starts_with(&chars, 8, "></"));
+    }
+}
@@ -0,0 +1,141 @@
+use gloo::storage::{LocalStorage, Storage};
+use indexed_db_futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+use crate::verify::{ProofReport, Status};
+
+const DB_NAME: &str = "proof_viz";
+const STORE_NAME: &str = "history";
+// Local storage key of the opt-in
+const ENABLED_KEY: &str = "proof_viz.history";
+
+/// A proof kept in the history, with the outcome of its last verification
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Content hash of the proof, the key of the entry
+    pub hash: String,
+    pub name: String,
+    /// The proof file
+    pub data: String,
+    pub server_name: Option<String>,
+    /// Notarization time, in seconds since the Unix epoch
+    pub time: Option<u64>,
+    pub status: String,
+    /// Fingerprint of the Notary key of the last verification
+    pub notary: String,
+    /// When the proof was stored, in milliseconds since the Unix epoch
+    pub saved_at: f64,
+}
+
+impl Entry {
+    pub fn new(name: &str, data: String, report: &ProofReport) -> Self {
+        let proof = report.result.as_ref().ok();
+        Self {
+            hash: report.proof_hash.clone(),
+            name: name.to_string(),
+            data,
+            server_name: proof.map(|proof| proof.server_name.clone()),
+            time: proof.map(|proof| proof.time),
+            status: report.status().to_string(),
+            notary: report.notary.clone(),
+            saved_at: js_sys::Date::now(),
+        }
+    }
+
+    /// Update the entry with the outcome of verifying it again
+    pub fn update(&mut self, report: &ProofReport) {
+        let proof = report.result.as_ref().ok();
+        self.server_name = proof.map(|proof| proof.server_name.clone());
+        self.time = proof.map(|proof| proof.time);
+        self.status = report.status().to_string();
+        self.notary = report.notary.clone();
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.status == Status::Verified.to_string()
+    }
+}
+
+/// Whether the user opted in to keep a history
+pub fn is_enabled() -> bool {
+    LocalStorage::get(ENABLED_KEY).unwrap_or(false)
+}
+
+pub fn set_enabled(enabled: bool) {
+    if enabled {
+        let _ = LocalStorage::set(ENABLED_KEY, true);
+    } else {
+        LocalStorage::delete(ENABLED_KEY);
+    }
+}
+
+fn error(err: impl Into<JsValue>) -> String {
+    let err: JsValue = err.into();
+    err.as_string().unwrap_or_else(|| format!("{:?}", err))
+}
+
+async fn open() -> Result<IdbDatabase, String> {
+    let mut request = IdbDatabase::open_u32(DB_NAME, 1).map_err(error)?;
+    request.set_on_upgrade_needed(Some(
+        |event: &IdbVersionChangeEvent| -> Result<(), JsValue> {
+            if !event
+                .db()
+                .object_store_names()
+                .any(|name| name == STORE_NAME)
+            {
+                event.db().create_object_store(STORE_NAME)?;
+            }
+            Ok(())
+        },
+    ));
+    request.await.map_err(error)
+}
+
+/// All stored entries, in no particular order
+pub async fn load() -> Result<Vec<Entry>, String> {
+    let db = open().await?;
+    let tx = db.transaction_on_one(STORE_NAME).map_err(error)?;
+    let store = tx.object_store(STORE_NAME).map_err(error)?;
+    let values = store.get_all().map_err(error)?.await.map_err(error)?;
+
+    Ok(values
+        .iter()
+        .filter_map(|value| serde_json::from_str(&value.as_string()?).ok())
+        .collect())
+}
+
+/// Add or replace an entry
+pub async fn save(entry: Entry) -> Result<(), String> {
+    let value = serde_json::to_string(&entry).map_err(|err| err.to_string())?;
+    let db = open().await?;
+    let tx = db
+        .transaction_on_one_with_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+        .map_err(error)?;
+    let store = tx.object_store(STORE_NAME).map_err(error)?;
+    store
+        .put_key_val_owned(entry.hash.as_str(), &JsValue::from_str(&value))
+        .map_err(error)?;
+    tx.await.into_result().map_err(error)
+}
+
+pub async fn delete(hash: String) -> Result<(), String> {
+    let db = open().await?;
+    let tx = db
+        .transaction_on_one_with_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+        .map_err(error)?;
+    let store = tx.object_store(STORE_NAME).map_err(error)?;
+    store.delete_owned(hash.as_str()).map_err(error)?;
+    tx.await.into_result().map_err(error)
+}
+
+/// Delete all entries
+pub async fn clear() -> Result<(), String> {
+    let db = open().await?;
+    let tx = db
+        .transaction_on_one_with_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+        .map_err(error)?;
+    let store = tx.object_store(STORE_NAME).map_err(error)?;
+    store.clear().map_err(error)?;
+    tx.await.into_result().map_err(error)
+}
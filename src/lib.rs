@@ -0,0 +1,21 @@
+pub mod artifact;
+pub mod bundle;
+pub mod certificate;
+mod charset;
+pub mod claims;
+pub mod compare;
+pub mod components;
+mod export;
+mod highlight;
+pub mod history;
+pub mod link;
+mod paste;
+pub mod policy;
+mod privacy;
+pub mod redaction;
+mod report;
+mod request;
+mod search;
+mod series;
+pub mod verify;
+pub mod worker;
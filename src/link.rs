@@ -0,0 +1,293 @@
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use elliptic_curve::sec1::ToEncodedPoint;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use web_sys::UrlSearchParams;
+
+use crate::verify::key_fingerprint;
+
+/// Longest `#proof=` fragment that is decoded
+pub const MAX_FRAGMENT_LEN: usize = 2 * 1024 * 1024;
+/// Largest proof that is accepted from a link, once decompressed
+pub const MAX_PROOF_LEN: usize = 16 * 1024 * 1024;
+
+/// Links longer than this may be cut off by browsers, chat apps or mail clients
+pub const PRACTICAL_URL_LEN: usize = 8 * 1024;
+
+/// What a link to the app asks to load
+#[derive(Default)]
+pub struct LinkParams {
+    /// `?proof_url=`, a proof to fetch
+    pub proof_url: Option<String>,
+    /// `#proof=`, a compressed proof
+    pub proof: Option<Result<Vec<u8>, String>>,
+    /// `#key=`, the Notary public key to verify with
+    pub key: Option<Result<p256::PublicKey, String>>,
+}
+
+impl LinkParams {
+    /// Read the parameters of the current page URL
+    pub fn from_location() -> Self {
+        let location = gloo::utils::window().location();
+        let query = location
+            .search()
+            .ok()
+            .and_then(|search| UrlSearchParams::new_with_str(&search).ok());
+        // The fragment is never sent to a server
+        let fragment = location.hash().ok().and_then(|hash| {
+            UrlSearchParams::new_with_str(hash.strip_prefix('#').unwrap_or(&hash)).ok()
+        });
+
+        Self {
+            proof_url: query.and_then(|query| query.get("proof_url")),
+            proof: fragment
+                .as_ref()
+                .and_then(|fragment| fragment.get("proof"))
+                .map(|proof| decode_proof(&proof)),
+            key: fragment
+                .as_ref()
+                .and_then(|fragment| fragment.get("key"))
+                .map(|key| decode_key(&key)),
+        }
+    }
+}
+
+/// Compress a proof into the value of a `#proof=` fragment
+pub fn encode_proof(data: &[u8]) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    // Writing to a `Vec` does not fail
+    let _ = encoder.write_all(data);
+    let compressed = encoder.finish().unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(compressed)
+}
+
+/// Decompress the value of a `#proof=` fragment
+pub fn decode_proof(fragment: &str) -> Result<Vec<u8>, String> {
+    if fragment.len() > MAX_FRAGMENT_LEN {
+        return Err(format!(
+            "The proof in the link is too large: {} characters, at most {} are accepted",
+            fragment.len(),
+            MAX_FRAGMENT_LEN
+        ));
+    }
+    let compressed = URL_SAFE_NO_PAD
+        .decode(fragment.trim_end_matches('='))
+        .map_err(|err| format!("The proof in the link is not valid base64url: {}", err))?;
+
+    let mut data = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_PROOF_LEN as u64 + 1)
+        .read_to_end(&mut data)
+        .map_err(|err| format!("The proof in the link is not valid deflate data: {}", err))?;
+    if data.len() > MAX_PROOF_LEN {
+        return Err(format!(
+            "The proof in the link is too large, at most {} bytes are accepted",
+            MAX_PROOF_LEN
+        ));
+    }
+    Ok(data)
+}
+
+/// The compressed SEC1 encoding of a key, as the value of `#key=`
+pub fn encode_key(key: &p256::PublicKey) -> String {
+    URL_SAFE_NO_PAD.encode(key.to_encoded_point(true).as_bytes())
+}
+
+pub fn decode_key(fragment: &str) -> Result<p256::PublicKey, String> {
+    URL_SAFE_NO_PAD
+        .decode(fragment.trim_end_matches('='))
+        .ok()
+        .and_then(|bytes| p256::PublicKey::from_sec1_bytes(&bytes).ok())
+        .ok_or_else(|| "The Notary key in the link is not a valid P-256 public key".to_string())
+}
+
+/// Download a proof for `?proof_url=`
+pub async fn fetch_proof(url: &str) -> Result<Vec<u8>, String> {
+    let response = gloo::net::http::Request::get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    if !response.ok() {
+        return Err(format!("{} {}", response.status(), response.status_text()));
+    }
+    let data = response.binary().await.map_err(|err| err.to_string())?;
+    if data.len() > MAX_PROOF_LEN {
+        return Err(format!(
+            "The proof is too large, at most {} bytes are accepted",
+            MAX_PROOF_LEN
+        ));
+    }
+    Ok(data)
+}
+
+/// File name of the proof at `url`
+pub fn file_name(url: &str) -> String {
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.trim_end_matches('/').rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(url)
+        .to_string()
+}
+
+/// A link to the app that opens a proof
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShareLink {
+    pub url: String,
+    /// Fingerprint of the Notary key the link proposes, if any
+    pub notary: Option<String>,
+    /// Whether the link was copied to the clipboard, `None` while copying
+    pub copied: Option<Result<(), String>>,
+}
+
+impl ShareLink {
+    /// A link that opens `data`, and proposes to verify it with `key`.
+    ///
+    /// Without a key the proof is verified with the key of whoever opens the link. A key in
+    /// the link is only used once they agree to it.
+    pub fn new(data: &[u8], key: Option<&p256::PublicKey>) -> Result<Self, String> {
+        let proof = encode_proof(data);
+        if proof.len() > MAX_FRAGMENT_LEN {
+            return Err(format!(
+                "The proof is too large to share as a link: {} characters compressed, at most {}",
+                proof.len(),
+                MAX_FRAGMENT_LEN
+            ));
+        }
+
+        let location = gloo::utils::window().location();
+        let base = format!(
+            "{}{}",
+            location.origin().unwrap_or_default(),
+            location.pathname().unwrap_or_default()
+        );
+        let mut url = format!("{}#proof={}", base, proof);
+        if let Some(key) = key {
+            url.push_str(&format!("&key={}", encode_key(key)));
+        }
+        Ok(Self {
+            url,
+            notary: key.map(key_fingerprint),
+            copied: None,
+        })
+    }
+
+    pub fn is_too_long(&self) -> bool {
+        self.url.len() > PRACTICAL_URL_LEN
+    }
+}
+
+pub async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let promise = gloo::utils::window()
+        .navigator()
+        .clipboard()
+        .write_text(text);
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.as_string().unwrap_or_else(|| format!("{:?}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use elliptic_curve::pkcs8::DecodePublicKey;
+
+    use super::*;
+    use crate::components::pem_input::DEFAULT_PEM;
+
+    #[test]
+    fn proof_roundtrip() {
+        let proof = br#"{"session": {}, "substrings": {}}"#.repeat(100);
+        let fragment = encode_proof(&proof);
+        assert!(fragment.len() < proof.len());
+        assert!(fragment
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(decode_proof(&fragment), Ok(proof.clone()));
+        // Padding some encoders add is ignored
+        assert_eq!(decode_proof(&format!("{}==", fragment)), Ok(proof));
+    }
+
+    #[test]
+    fn fragment_too_long() {
+        let fragment = "A".repeat(MAX_FRAGMENT_LEN + 1);
+        let err = decode_proof(&fragment).unwrap_err();
+        assert!(
+            err.starts_with("The proof in the link is too large"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn deflate_bomb() {
+        let fragment = encode_proof(&vec![0; MAX_PROOF_LEN + 1]);
+        assert!(fragment.len() <= MAX_FRAGMENT_LEN);
+        assert_eq!(
+            decode_proof(&fragment),
+            Err(format!(
+                "The proof in the link is too large, at most {} bytes are accepted",
+                MAX_PROOF_LEN
+            ))
+        );
+        assert_eq!(
+            decode_proof(&encode_proof(&vec![0; MAX_PROOF_LEN])).map(|data| data.len()),
+            Ok(MAX_PROOF_LEN)
+        );
+    }
+
+    #[test]
+    fn invalid_fragment() {
+        let err = decode_proof("not base64!").unwrap_err();
+        assert!(
+            err.starts_with("The proof in the link is not valid base64url"),
+            "{}",
+            err
+        );
+        // Standard base64 characters are not in the URL-safe alphabet
+        assert!(decode_proof("ab+/").is_err());
+
+        let err = decode_proof(&URL_SAFE_NO_PAD.encode(b"not deflate data")).unwrap_err();
+        assert!(
+            err.starts_with("The proof in the link is not valid deflate data"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn key_roundtrip() {
+        let key = p256::PublicKey::from_public_key_pem(DEFAULT_PEM).unwrap();
+        assert_eq!(decode_key(&encode_key(&key)), Ok(key));
+    }
+
+    #[test]
+    fn malformed_key() {
+        let key = p256::PublicKey::from_public_key_pem(DEFAULT_PEM).unwrap();
+        let encoded = URL_SAFE_NO_PAD.decode(encode_key(&key)).unwrap();
+        let error = Err("The Notary key in the link is not a valid P-256 public key".to_string());
+
+        assert_eq!(decode_key("not a key"), error);
+        assert_eq!(decode_key(""), error);
+        // Cut off
+        assert_eq!(decode_key(&URL_SAFE_NO_PAD.encode(&encoded[..20])), error);
+        // Not a point on the curve
+        let mut off_curve = encoded.clone();
+        off_curve[1..].fill(0xff);
+        assert_eq!(decode_key(&URL_SAFE_NO_PAD.encode(&off_curve)), error);
+    }
+
+    #[test]
+    fn file_names() {
+        assert_eq!(
+            file_name("https://a.example/p/proof.json?x=1#y"),
+            "proof.json"
+        );
+        assert_eq!(file_name("https://a.example/p/"), "p");
+        assert_eq!(file_name("proof.json"), "proof.json");
+    }
+}
@@ -1,89 +1,714 @@
 extern crate base64;
+use gloo::events::EventListener;
 use gloo::file::callbacks::FileReader;
 use gloo::file::File;
-use std::collections::HashMap;
+use gloo::timers::callback::Timeout;
+use gloo::worker::{Spawnable, WorkerBridge};
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
 
 #[allow(unused_imports)]
 use gloo::console::log;
 
 use web_sys::{DragEvent, Event, FileList, HtmlInputElement};
+use yew::html::Scope;
 use yew::html::TargetCast;
 use yew::prelude::*;
 
-mod components;
-use crate::components::pem_input::PemInputComponent;
-use crate::components::pem_input::DEFAULT_PEM;
-use crate::components::view_file::ViewFile;
-use elliptic_curve::pkcs8::DecodePublicKey;
+use elliptic_curve::pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding};
+use proof_viz::bundle::{self, Bundle, BundleExport};
+use proof_viz::claims::template::{self, Template};
+use proof_viz::claims::Registry;
+use proof_viz::compare::Candidate;
+use proof_viz::components::compare_view::CompareComponent;
+use proof_viz::components::dashboard::{DashboardComponent, Row};
+use proof_viz::components::history_sidebar::HistorySidebar;
+use proof_viz::components::paste_input::PasteInputComponent;
+use proof_viz::components::pem_input::PemInputComponent;
+use proof_viz::components::pem_input::DEFAULT_PEM;
+use proof_viz::components::policy_input::PolicyInputComponent;
+use proof_viz::components::redaction_settings::RedactionSettingsComponent;
+use proof_viz::components::template_settings::TemplateSettingsComponent;
+use proof_viz::components::time_series::TimeSeriesComponent;
+use proof_viz::components::view_file::{card_id, ViewFile};
+use proof_viz::history::{self, Entry};
+use proof_viz::link::{copy_to_clipboard, fetch_proof, file_name, LinkParams, ShareLink};
+use proof_viz::policy::{self, Policy};
+use proof_viz::redaction::RedactionStyle;
+use proof_viz::verify::{content_hash, key_fingerprint, FileState, ProofReport, Step, SuppliedKey};
+use proof_viz::worker::{verify_request, Request, Response, VerifierWorker, WORKER_PATH};
 
-#[derive(Properties, PartialEq)]
 struct FileDetails {
+    /// Unique id of the file, names are not unique
+    id: usize,
     name: String,
-    file_type: String,
     data: Vec<u8>,
+    /// Content hash, to skip proofs that were already dropped
+    hash: Option<String>,
+    state: FileState,
+    /// Link to the proof, once the user asked for it
+    share: Option<Result<ShareLink, String>>,
+    /// Offline bundle of the proof, once the user asked for it
+    bundle: Option<BundleExport>,
 }
 
 pub enum Msg {
-    Loaded(String, String, Vec<u8>),
+    Loaded(usize, Result<Vec<u8>, String>),
+    /// A proof pasted as text
+    Pasted(Vec<u8>),
+    Worker(Response),
+    HistoryWorker(Response),
+    History(Vec<Entry>),
+    HistoryToggle(bool),
+    HistoryOpen(String),
+    HistoryDelete(String),
+    HistoryClear,
     Files(Vec<File>),
+    Cancel(usize),
+    Close(usize),
+    ClearAll,
     Pem(p256::PublicKey),
+    Redaction(RedactionStyle),
+    Policy(Option<Rc<Policy>>),
+    Templates(Vec<Template>),
+    /// The fragment of the page URL changed
+    Link,
+    /// Whether to verify with the Notary key of the link
+    LinkKey(bool),
+    /// Create a link to a proof
+    Share(usize),
+    /// Whether the link to a proof was copied to the clipboard
+    Copied(usize, Result<(), String>),
+    /// Save an offline bundle of a proof
+    Bundle(usize),
+    Bundled(usize, Result<(), String>),
 }
 
 pub struct App {
-    readers: HashMap<String, FileReader>,
+    readers: HashMap<usize, FileReader>,
     files: Vec<FileDetails>,
+    next_id: usize,
+    /// Names of the files that were skipped because the same proof is already shown
+    duplicates: Vec<String>,
+    verifier: Verifier,
+    /// Files waiting for verification, one file is verified at a time so it can be cancelled
+    queue: VecDeque<usize>,
+    /// The file that is being verified by the worker
+    verifying: Option<usize>,
+    /// Reports by proof hash and Notary key fingerprint, so a proof is only verified once per key
+    reports: HashMap<(String, String), Rc<ProofReport>>,
+    /// Whether the user opted in to keep verified proofs in IndexedDB
+    history_enabled: bool,
+    history: Vec<Entry>,
+    /// Verifies the stored proofs again when the Notary key changes
+    history_worker: Option<WorkerBridge<VerifierWorker>>,
+    /// Hashes of the entries sent to `history_worker`, indexed by request id
+    history_jobs: Vec<String>,
     pem: p256::PublicKey,
-    is_processing: bool,
+    /// Set when `pem` came with the proof rather than from the user
+    supplied_key: Option<SuppliedKey>,
+    /// Notary key of the link the app was opened with, until the user chose to use it or not
+    link_key: Option<p256::PublicKey>,
+    redaction: RedactionStyle,
+    /// Rules every verified proof is checked against
+    policy: Option<Rc<Policy>>,
+    /// Claim extractors of the known services
+    claims: Rc<Registry>,
+    /// Invalid parameters of the link the app was opened with
+    link_errors: Vec<String>,
+    _hash_listener: EventListener,
+}
+
+/// Where proofs are verified
+enum Verifier {
+    Worker(WorkerBridge<VerifierWorker>),
+    /// On the main thread, for offline bundles opened from disk, which can not start workers
+    Local(Scope<App>),
+}
+
+impl Verifier {
+    fn send(&self, request: Request) {
+        match self {
+            Verifier::Worker(worker) => worker.send(request),
+            Verifier::Local(link) => {
+                let link = link.clone();
+                // Let the page show the progress before the verification blocks it
+                Timeout::new(0, move || {
+                    let id = request.id;
+                    let report = verify_request(&request, |_| {});
+                    link.send_message(Msg::Worker(Response::Done { id, report }));
+                })
+                .forget();
+            }
+        }
+    }
+}
+
+impl App {
+    fn spawn_worker(ctx: &Context<Self>) -> Verifier {
+        let link = ctx.link().clone();
+        Verifier::Worker(
+            VerifierWorker::spawner()
+                .callback(move |response| link.send_message(Msg::Worker(response)))
+                .spawn(WORKER_PATH),
+        )
+    }
+
+    /// Add or update the history entry of a verified file
+    fn remember(&mut self, id: usize) {
+        let Some(file) = self.files.iter().find(|file| file.id == id) else {
+            return;
+        };
+        let FileState::Done(report) = &file.state else {
+            return;
+        };
+        let stored = self
+            .history
+            .iter_mut()
+            .find(|entry| entry.hash == report.proof_hash);
+        let entry = match stored {
+            Some(entry) => {
+                entry.update(report);
+                entry.clone()
+            }
+            None if report.result.is_ok() => {
+                let data = String::from_utf8_lossy(&file.data).into_owned();
+                let entry = Entry::new(&file.name, data, report);
+                self.history.push(entry.clone());
+                entry
+            }
+            None => return,
+        };
+        yew::platform::spawn_local(async move {
+            if let Err(err) = history::save(entry).await {
+                log!(format!("Saving to the history failed {}", err));
+            }
+        });
+    }
+
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.history_worker = None;
+        self.history_jobs.clear();
+        yew::platform::spawn_local(async {
+            if let Err(err) = history::clear().await {
+                log!(format!("Deleting the history failed {}", err));
+            }
+        });
+    }
+
+    /// Verify the stored proofs that were verified with another Notary key
+    fn verify_history(&mut self, ctx: &Context<Self>) {
+        self.history_worker = None;
+        self.history_jobs.clear();
+
+        let notary = key_fingerprint(&self.pem);
+        let notary_key = self.pem.to_sec1_bytes().to_vec();
+        for entry in self
+            .history
+            .iter_mut()
+            .filter(|entry| entry.notary != notary)
+        {
+            if let Some(report) = self.reports.get(&(entry.hash.clone(), notary.clone())) {
+                entry.update(report);
+                let entry = entry.clone();
+                yew::platform::spawn_local(async move {
+                    let _ = history::save(entry).await;
+                });
+                continue;
+            }
+
+            let worker = self.history_worker.get_or_insert_with(|| {
+                let link = ctx.link().clone();
+                VerifierWorker::spawner()
+                    .callback(move |response| link.send_message(Msg::HistoryWorker(response)))
+                    .spawn(WORKER_PATH)
+            });
+            worker.send(Request {
+                id: self.history_jobs.len(),
+                data: entry.data.clone().into_bytes(),
+                notary_key: notary_key.clone(),
+            });
+            self.history_jobs.push(entry.hash.clone());
+        }
+    }
+
+    fn file_mut(&mut self, id: usize) -> Option<&mut FileDetails> {
+        self.files.iter_mut().find(|file| file.id == id)
+    }
+
+    fn enqueue(&mut self, id: usize) {
+        let notary = key_fingerprint(&self.pem);
+        let Some(file) = self.files.iter_mut().find(|file| file.id == id) else {
+            return;
+        };
+        let cached = file
+            .hash
+            .clone()
+            .and_then(|hash| self.reports.get(&(hash, notary)));
+        match cached {
+            Some(report) => file.state = FileState::Done(report.clone()),
+            None => {
+                file.state = FileState::Queued;
+                self.queue.push_back(id);
+            }
+        }
+        self.verify_next();
+    }
+
+    /// Send the next queued file to the worker, unless it is busy
+    fn verify_next(&mut self) {
+        if self.verifying.is_some() {
+            return;
+        }
+        while let Some(id) = self.queue.pop_front() {
+            let notary_key = self.pem.to_sec1_bytes().to_vec();
+            let Some(file) = self.files.iter_mut().find(|file| file.id == id) else {
+                continue;
+            };
+            file.state = FileState::Verifying(Step::Parsing);
+            self.verifier.send(Request {
+                id,
+                data: file.data.clone(),
+                notary_key,
+            });
+            self.verifying = Some(id);
+            return;
+        }
+    }
+
+    /// Add a file that is being read
+    fn add_file(&mut self, name: String) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.files.push(FileDetails {
+            id,
+            name,
+            data: Vec::new(),
+            hash: None,
+            state: FileState::Loading,
+            share: None,
+            bundle: None,
+        });
+        id
+    }
+
+    /// Verify with another Notary key. `source` is what supplied the key, if not the user.
+    fn set_pem(&mut self, ctx: &Context<Self>, pem: p256::PublicKey, source: Option<&'static str>) {
+        self.pem = pem;
+        self.supplied_key = source.map(|source| SuppliedKey {
+            fingerprint: key_fingerprint(&pem),
+            source,
+            differs: pem != default_pem(),
+        });
+        // The running verification is for the old key
+        self.stop_worker(ctx);
+        self.queue.clear();
+        let ids: Vec<usize> = self
+            .files
+            .iter()
+            .filter(|file| {
+                file.hash.is_some()
+                    && matches!(
+                        file.state,
+                        FileState::Queued
+                            | FileState::Verifying(_)
+                            | FileState::Done(_)
+                            | FileState::Cancelled
+                    )
+            })
+            .map(|file| file.id)
+            .collect();
+        for id in ids {
+            self.enqueue(id);
+        }
+        // The history is only verified again with keys the user chose
+        if source.is_none() {
+            self.verify_history(ctx);
+        }
+    }
+
+    /// Load the proof and Notary key a link to the app points to
+    fn open_link(&mut self, ctx: &Context<Self>, link: LinkParams) {
+        match link.key {
+            // The key is only used once the user agreed to it
+            Some(Ok(key)) if key != self.pem => self.link_key = Some(key),
+            Some(Ok(_)) => {}
+            Some(Err(err)) => self.link_errors.push(err),
+            None => {}
+        }
+        match link.proof {
+            Some(Ok(data)) => {
+                let id = self.add_file("Linked proof".to_string());
+                self.loaded(id, Ok(data));
+            }
+            Some(Err(err)) => self.link_errors.push(err),
+            None => {}
+        }
+        if let Some(url) = link.proof_url {
+            let id = self.add_file(file_name(&url));
+            ctx.link().send_future(async move {
+                let data = fetch_proof(&url)
+                    .await
+                    .map_err(|err| format!("from {}: {}", url, err));
+                Msg::Loaded(id, data)
+            });
+        }
+    }
+
+    /// Check a file that was read, and queue it for verification
+    fn loaded(&mut self, id: usize, data: Result<Vec<u8>, String>) -> bool {
+        self.readers.remove(&id);
+        let data = match data {
+            Ok(data) => data,
+            Err(err) => {
+                if let Some(file) = self.files.iter_mut().find(|file| file.id == id) {
+                    file.state = FileState::Failed(format!("Reading failed {}", err));
+                }
+                return true;
+            }
+        };
+
+        let hash = content_hash(&data);
+        let duplicate = self
+            .files
+            .iter()
+            .any(|file| file.hash.as_ref() == Some(&hash));
+        let Some(index) = self.files.iter().position(|file| file.id == id) else {
+            return false;
+        };
+        if duplicate {
+            let file = self.files.remove(index);
+            self.duplicates.push(file.name);
+            return true;
+        }
+
+        let file = &mut self.files[index];
+        file.data = data;
+        file.hash = Some(hash);
+        // Files of any type are verified, so keys, configurations and other files opened by
+        // mistake get explained
+        self.enqueue(id);
+        true
+    }
+
+    /// Stop reading or verifying a file
+    fn cancel(&mut self, ctx: &Context<Self>, id: usize) {
+        self.readers.remove(&id);
+        self.queue.retain(|queued| *queued != id);
+        if self.verifying == Some(id) {
+            self.stop_worker(ctx);
+        }
+        if let Some(file) = self.file_mut(id) {
+            if file.state.is_pending() {
+                file.state = FileState::Cancelled;
+            }
+        }
+        self.verify_next();
+    }
+
+    /// Stop the verification that is running, by replacing the worker
+    fn stop_worker(&mut self, ctx: &Context<Self>) {
+        // A verification on the main thread can not be stopped, its result is dropped
+        if self.verifying.take().is_some() && matches!(self.verifier, Verifier::Worker(_)) {
+            self.verifier = Self::spawn_worker(ctx);
+        }
+    }
+
+    /// Verify the proof of an offline bundle
+    fn open_bundle(&mut self, ctx: &Context<Self>, bundle: Bundle) {
+        // The bundle brings its own key, which a forged bundle would too
+        self.set_pem(ctx, bundle.key, Some("the offline bundle"));
+        let id = self.add_file(bundle.name);
+        self.loaded(id, Ok(bundle.data));
+    }
 }
 
 impl Component for App {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let bundle = bundle::embedded();
+        // Offline bundles keep no history, they are opened from disk
+        let history_enabled = bundle.is_none() && history::is_enabled();
+        if history_enabled {
+            ctx.link().send_future(async {
+                Msg::History(history::load().await.unwrap_or_else(|err| {
+                    log!(format!("Loading the history failed {}", err));
+                    Vec::new()
+                }))
+            });
+        }
+
+        let mut app = Self {
             readers: HashMap::default(),
             files: Vec::default(),
-            pem: p256::PublicKey::from_public_key_pem(DEFAULT_PEM).unwrap(),
-            is_processing: false,
+            next_id: 0,
+            duplicates: Vec::default(),
+            verifier: match bundle {
+                Some(_) => Verifier::Local(ctx.link().clone()),
+                None => Self::spawn_worker(ctx),
+            },
+            queue: VecDeque::default(),
+            verifying: None,
+            reports: HashMap::default(),
+            history_enabled,
+            history: Vec::default(),
+            history_worker: None,
+            history_jobs: Vec::default(),
+            pem: default_pem(),
+            supplied_key: None,
+            link_key: None,
+            redaction: RedactionStyle::default(),
+            policy: policy::load_text()
+                .and_then(|text| Policy::parse(&text).ok())
+                .map(Rc::new),
+            claims: Rc::new(Registry::with_templates(template::parse_all(
+                &template::load(),
+            ))),
+            link_errors: Vec::default(),
+            _hash_listener: {
+                let link = ctx.link().clone();
+                EventListener::new(&gloo::utils::window(), "hashchange", move |_| {
+                    link.send_message(Msg::Link)
+                })
+            },
+        };
+        match bundle {
+            Some(Ok(bundle)) => app.open_bundle(ctx, bundle),
+            Some(Err(err)) => app.link_errors.push(err),
+            None => app.open_link(ctx, LinkParams::from_location()),
         }
+        app
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            Msg::Loaded(file_name, file_type, data) => {
+            Msg::Loaded(id, data) => self.loaded(id, data),
+            Msg::Pasted(data) => {
+                self.duplicates.clear();
+                let id = self.add_file("Pasted proof".to_string());
+                self.loaded(id, Ok(data))
+            }
+            Msg::Worker(Response::Progress { id, step }) => {
+                if self.verifying != Some(id) {
+                    return false;
+                }
+                if let Some(file) = self.file_mut(id) {
+                    file.state = FileState::Verifying(step);
+                }
+                true
+            }
+            Msg::Worker(Response::Done { id, report }) => {
+                // Results of cancelled verifications are dropped with their worker
+                if self.verifying != Some(id) {
+                    return false;
+                }
+                self.verifying = None;
+                let report = Rc::new(report);
+                self.reports.insert(
+                    (report.proof_hash.clone(), report.notary.clone()),
+                    report.clone(),
+                );
+                if let Some(file) = self.file_mut(id) {
+                    file.state = FileState::Done(report);
+                }
+                if self.history_enabled {
+                    self.remember(id);
+                }
+                self.verify_next();
+                true
+            }
+            Msg::HistoryWorker(Response::Progress { .. }) => false,
+            Msg::HistoryWorker(Response::Done { id, report }) => {
+                let Some(hash) = self.history_jobs.get(id) else {
+                    return false;
+                };
+                let Some(entry) = self.history.iter_mut().find(|entry| entry.hash == *hash) else {
+                    return false;
+                };
+                entry.update(&report);
+                let entry = entry.clone();
+                yew::platform::spawn_local(async move {
+                    let _ = history::save(entry).await;
+                });
+                let report = Rc::new(report);
+                self.reports
+                    .insert((report.proof_hash.clone(), report.notary.clone()), report);
+                true
+            }
+            Msg::History(entries) => {
+                self.history = entries;
+                self.verify_history(ctx);
+                true
+            }
+            Msg::HistoryToggle(enabled) => {
+                history::set_enabled(enabled);
+                self.history_enabled = enabled;
+                if enabled {
+                    let ids: Vec<usize> = self.files.iter().map(|file| file.id).collect();
+                    for id in ids {
+                        self.remember(id);
+                    }
+                } else {
+                    self.clear_history();
+                }
+                true
+            }
+            Msg::HistoryOpen(hash) => {
+                if let Some(file) = self
+                    .files
+                    .iter()
+                    .find(|file| file.hash.as_ref() == Some(&hash))
+                {
+                    if let Some(card) = gloo::utils::document().get_element_by_id(&card_id(file.id))
+                    {
+                        card.scroll_into_view();
+                    }
+                    return false;
+                }
+                let Some(entry) = self.history.iter().find(|entry| entry.hash == hash) else {
+                    return false;
+                };
+                let id = self.next_id;
+                self.next_id += 1;
                 self.files.push(FileDetails {
-                    data,
-                    file_type,
-                    name: file_name.clone(),
+                    id,
+                    name: entry.name.clone(),
+                    data: entry.data.clone().into_bytes(),
+                    hash: Some(hash),
+                    state: FileState::Loading,
+                    share: None,
+                    bundle: None,
+                });
+                self.enqueue(id);
+                true
+            }
+            Msg::HistoryDelete(hash) => {
+                self.history.retain(|entry| entry.hash != hash);
+                yew::platform::spawn_local(async move {
+                    if let Err(err) = history::delete(hash).await {
+                        log!(format!("Deleting from the history failed {}", err));
+                    }
                 });
-                self.readers.remove(&file_name);
-                self.is_processing = false;
+                true
+            }
+            Msg::HistoryClear => {
+                self.clear_history();
+                true
+            }
+            Msg::Cancel(id) => {
+                self.cancel(ctx, id);
+                true
+            }
+            Msg::Close(id) => {
+                self.cancel(ctx, id);
+                self.files.retain(|file| file.id != id);
+                true
+            }
+            Msg::ClearAll => {
+                self.readers.clear();
+                self.queue.clear();
+                self.stop_worker(ctx);
+                self.files.clear();
+                self.duplicates.clear();
                 true
             }
             Msg::Pem(pem) => {
-                self.pem = pem;
+                self.link_key = None;
+                self.set_pem(ctx, pem, None);
+                true
+            }
+            Msg::LinkKey(use_key) => {
+                if let Some(key) = self.link_key.take() {
+                    if use_key {
+                        self.set_pem(ctx, key, Some("the link"));
+                    }
+                }
+                true
+            }
+            Msg::Link => {
+                // Only the fragment changes without reloading the page
+                let link = LinkParams {
+                    proof_url: None,
+                    ..LinkParams::from_location()
+                };
+                self.link_errors.clear();
+                self.link_key = None;
+                self.open_link(ctx, link);
+                true
+            }
+            Msg::Share(id) => {
+                // The default key is left out, links only propose other keys
+                let key = (self.pem != default_pem()).then_some(self.pem);
+                let Some(file) = self.file_mut(id) else {
+                    return false;
+                };
+                // Only verified proofs are shared
+                if !matches!(&file.state, FileState::Done(report) if report.result.is_ok()) {
+                    return false;
+                }
+                let share = ShareLink::new(&file.data, key.as_ref());
+                if let Ok(share) = &share {
+                    let url = share.url.clone();
+                    ctx.link()
+                        .send_future(async move { Msg::Copied(id, copy_to_clipboard(&url).await) });
+                }
+                file.share = Some(share);
+                true
+            }
+            Msg::Copied(id, copied) => {
+                if let Some(Some(Ok(share))) = self.file_mut(id).map(|file| &mut file.share) {
+                    share.copied = Some(copied);
+                }
+                true
+            }
+            Msg::Bundle(id) => {
+                let pem = self.pem;
+                let Some(file) = self.file_mut(id) else {
+                    return false;
+                };
+                let (name, data) = (file.name.clone(), file.data.clone());
+                ctx.link().send_future(async move {
+                    Msg::Bundled(id, bundle::save(name, data, pem).await)
+                });
+                file.bundle = Some(BundleExport::Building);
+                true
+            }
+            Msg::Bundled(id, saved) => {
+                if let Some(file) = self.file_mut(id) {
+                    file.bundle = Some(match saved {
+                        Ok(()) => BundleExport::Saved,
+                        Err(err) => BundleExport::Failed(err),
+                    });
+                }
+                true
+            }
+            Msg::Redaction(redaction) => {
+                self.redaction = redaction;
+                true
+            }
+            Msg::Policy(policy) => {
+                self.policy = policy;
+                true
+            }
+            Msg::Templates(templates) => {
+                self.claims = Rc::new(Registry::with_templates(templates));
                 true
             }
             Msg::Files(files) => {
-                self.is_processing = true;
+                self.duplicates.clear();
                 for file in files.into_iter() {
-                    let file_name = file.name();
-                    let file_type = file.raw_mime_type();
+                    let id = self.add_file(file.name());
 
                     let task = {
                         let link = ctx.link().clone();
-                        let file_name = file_name.clone();
-
                         gloo::file::callbacks::read_as_bytes(&file, move |res| {
-                            link.send_message(Msg::Loaded(
-                                file_name,
-                                file_type,
-                                res.expect("failed to read file"),
-                            ))
+                            link.send_message(Msg::Loaded(id, res.map_err(|err| err.to_string())))
                         })
                     };
-                    self.readers.insert(file_name, task);
+                    self.readers.insert(id, task);
                 }
                 true
             }
@@ -126,6 +751,37 @@ impl Component for App {
             </svg>
         };
 
+        let rows: Vec<Row> = self
+            .files
+            .iter()
+            .filter_map(|file| match &file.state {
+                FileState::Done(report) => Some(Row::new(file.id, &file.name, report)),
+                _ => None,
+            })
+            .collect();
+        let proofs: Vec<Candidate> = self
+            .files
+            .iter()
+            .filter_map(|file| match &file.state {
+                FileState::Done(report) => Some(Candidate {
+                    id: file.id,
+                    name: file.name.clone(),
+                    report: report.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
+        let verified: Vec<Candidate> = proofs
+            .iter()
+            .filter(|proof| proof.report.result.is_ok())
+            .cloned()
+            .collect();
+        let pending = self
+            .files
+            .iter()
+            .filter(|file| file.state.is_pending())
+            .count();
+
         html! {
         <div class="flex flex-col h-screen">
             <nav class="bg-zinc-700 h-16 px-8 py-2">
@@ -143,6 +799,16 @@ impl Component for App {
                     })}
                 </div>
             </nav>
+            <div class="flex">
+            <HistorySidebar
+                enabled={self.history_enabled}
+                entries={self.history.clone()}
+                notary={key_fingerprint(&self.pem)}
+                on_toggle={ctx.link().callback(Msg::HistoryToggle)}
+                on_open={ctx.link().callback(Msg::HistoryOpen)}
+                on_delete={ctx.link().callback(Msg::HistoryDelete)}
+                on_clear={ctx.link().callback(|_| Msg::HistoryClear)}
+            />
             <div class="w-4/5 m-auto">
                 // <p class="text-2xl text-center">{ "Upload Your TLSNotary Proof" }</p>
 
@@ -167,7 +833,6 @@ impl Component for App {
                     id="file-upload"
                     class="invisible"
                     type="file"
-                    accept="application/json"
                     multiple={true}
                     onchange={ctx.link().callback(move |e: Event| {
                         let input: HtmlInputElement = e.target_unchecked_into();
@@ -175,26 +840,106 @@ impl Component for App {
                     })}
                 />
 
-                if self.is_processing {
+                if pending > 0 {
                     <div role="status" class="p-16 flex flex-col justify-center items-center bg-zinc-700 border border-white border-dashed rounded-2xl">
                         {progress_icon}
-                        <span>{"Processing..."}</span>
+                        <span>{format!("Processing {} of {} files...", pending, self.files.len())}</span>
                     </div>
                     }
 
-                <PemInputComponent pem_callback={ctx.link().callback(Msg::Pem)}/>
+                {for self.link_errors.iter().map(|error| html! {
+                    <div class="container mx-auto p-4" role="alert">
+                        <div class="bg-red-500 text-white font-bold rounded-t px-4 py-2">
+                            {"Invalid link"}
+                        </div>
+                        <div class="border border-t-0 border-red-400 rounded-b bg-red-100 px-4 py-3 text-red-700">
+                            { "❌ " }{error}
+                        </div>
+                    </div>
+                })}
+
+                if let Some(key) = &self.supplied_key {
+                    <div class="container mx-auto p-4" role="alert">
+                        <div class={classes!("font-bold", "rounded-t", "px-4", "py-2", if key.differs { "bg-red-500 text-white" } else { "bg-yellow-500 text-black" })}>
+                            {format!("Notary key supplied by {}", key.source)}
+                        </div>
+                        <div class="border border-t-0 border-yellow-400 rounded-b bg-yellow-100 px-4 py-3 text-yellow-900">
+                            <p class="font-mono text-lg break-all">{key.fingerprint.clone()}</p>
+                            <p>
+                                {format!("Proofs are verified with this key from {}. Anyone can sign a proof with their own key: compare its fingerprint with the key of the Notary you trust before relying on a proof.", key.source)}
+                            </p>
+                            if key.differs {
+                                <p class="font-bold text-red-700">{"⚠️ It is not the default Notary key."}</p>
+                            }
+                        </div>
+                    </div>
+                }
+
+                if let Some(key) = &self.link_key {
+                    <div class="container mx-auto p-4" role="alert">
+                        <div class="bg-yellow-500 text-black font-bold rounded-t px-4 py-2">
+                            {"The link comes with another Notary key"}
+                        </div>
+                        <div class="border border-t-0 border-yellow-400 rounded-b bg-yellow-100 px-4 py-3 text-yellow-900">
+                            <p>
+                                {"The link asks to verify with Notary key "}
+                                <span class="font-mono break-all">{key_fingerprint(key)}</span>
+                                {". Anyone can sign a proof with their own key, so a proof verified with it is only as trustworthy as the key. Only use it if it is the key of a Notary you trust."}
+                            </p>
+                            <div class="flex gap-2 mt-2">
+                                <button class="px-4 py-1 rounded border border-yellow-900 hover:bg-yellow-200" onclick={ctx.link().callback(|_| Msg::LinkKey(true))}>{"Use the key of the link"}</button>
+                                <button class="px-4 py-1 rounded border border-yellow-900 hover:bg-yellow-200" onclick={ctx.link().callback(|_| Msg::LinkKey(false))}>{"Keep the current key"}</button>
+                            </div>
+                        </div>
+                    </div>
+                }
+
+                <PasteInputComponent proof_callback={ctx.link().callback(Msg::Pasted)}/>
+                <PemInputComponent pem_callback={ctx.link().callback(Msg::Pem)} initial_pem={self.pem.to_public_key_pem(LineEnding::LF).ok()}/>
+                <RedactionSettingsComponent style={self.redaction} style_callback={ctx.link().callback(Msg::Redaction)}/>
+                <PolicyInputComponent policy_callback={ctx.link().callback(Msg::Policy)}/>
+                <TemplateSettingsComponent templates_callback={ctx.link().callback(Msg::Templates)}/>
+
+                if !self.duplicates.is_empty() {
+                    <p class="p-4 text-sm text-gray-400 text-center">
+                        {format!("Skipped already loaded proofs: {}", self.duplicates.join(", "))}
+                    </p>
+                }
+
+                if !self.files.is_empty() {
+                    <div class="container flex mx-auto px-4 justify-end">
+                        <button class="px-4 py-2 hover:bg-black hover:text-white rounded border-white border" onclick={ctx.link().callback(|_| Msg::ClearAll)}>{"Clear all"}</button>
+                    </div>
+                }
+
+                if rows.len() > 1 {
+                    <DashboardComponent rows={rows} />
+                }
+
+                if verified.len() > 1 {
+                    <CompareComponent candidates={verified} redaction={self.redaction} />
+                }
+
+                if proofs.len() > 1 {
+                    <TimeSeriesComponent proofs={proofs} />
+                }
 
                 <div>
-                    {for self.files.iter().rev().map(|file| html! {
-                        <ViewFile name={file.name.clone()} file_type={file.file_type.clone()} data={file.data.clone()} pem={self.pem} />
-                    })}
+                    {for self.files.iter().rev().map(|file| { let id = file.id; html! {
+                        <ViewFile key={file.id} id={file.id} name={file.name.clone()} state={file.state.clone()} redaction={self.redaction} on_cancel={ctx.link().callback(move |_| Msg::Cancel(id))} on_close={ctx.link().callback(move |_| Msg::Close(id))} share={file.share.clone()} bundle={file.bundle.clone()} on_bundle={ctx.link().callback(move |_| Msg::Bundle(id))} policy={self.policy.clone()} claims={self.claims.clone()} supplied_key={self.supplied_key.clone()} on_share={ctx.link().callback(move |_| Msg::Share(id))} />
+                    }})}
                 </div>
             </div>
+            </div>
         </div>
         }
     }
 }
 
+fn default_pem() -> p256::PublicKey {
+    p256::PublicKey::from_public_key_pem(DEFAULT_PEM).unwrap()
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
 
@@ -0,0 +1,195 @@
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use tlsn_core::proof::TlsProof;
+
+use crate::artifact::identify;
+
+// How deep to look for a proof inside a JSON envelope
+const MAX_DEPTH: usize = 4;
+
+/// Whether a JSON value looks like a `TlsProof`
+fn is_proof(value: &serde_json::Value) -> bool {
+    value.get("session").is_some() && value.get("substrings").is_some()
+}
+
+/// Find a proof in a JSON value: the value itself, a proof nested in an envelope like
+/// `{"proof": {...}}`, or an encoded proof in a string field of the envelope
+fn find_proof(value: &serde_json::Value, depth: usize) -> Option<Vec<u8>> {
+    if is_proof(value) {
+        return serde_json::to_vec(value).ok();
+    }
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+    match value {
+        serde_json::Value::Object(fields) => fields
+            .values()
+            .find_map(|field| find_proof(field, depth + 1)),
+        serde_json::Value::Array(items) => {
+            items.iter().find_map(|item| find_proof(item, depth + 1))
+        }
+        serde_json::Value::String(text) => decode_text(text, depth + 1).ok(),
+        _ => None,
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if text.is_empty() || text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(text).ok())
+}
+
+/// Decode the bytes of a JSON or bincode proof into a JSON proof
+fn decode_bytes(bytes: &[u8], depth: usize) -> Result<Vec<u8>, String> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if text.trim_start().starts_with(['{', '[']) {
+            return decode_text(text, depth);
+        }
+    }
+    let proof: TlsProof =
+        bincode::deserialize(bytes).map_err(|err| format!("Not a bincode proof: {}", err))?;
+    serde_json::to_vec(&proof).map_err(|err| err.to_string())
+}
+
+/// Explain a pasted TLSNotary file that is not a proof, or give `error`
+fn not_a_proof(text: &str, error: &str) -> String {
+    match identify(text.as_bytes()) {
+        Some(artifact) => artifact.explain(),
+        None => error.to_string(),
+    }
+}
+
+fn decode_text(text: &str, depth: usize) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if text.starts_with(['{', '[']) {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|err| format!("Invalid JSON: {}", err))?;
+        // Keep a proof as it is, so it has the same hash as the file it was copied from
+        if is_proof(&value) {
+            return Ok(text.as_bytes().to_vec());
+        }
+        return find_proof(&value, depth)
+            .ok_or_else(|| not_a_proof(text, "No proof found in the JSON"));
+    }
+
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = decode_hex(&compact)
+        .or_else(|| decode_base64(&compact))
+        .ok_or_else(|| not_a_proof(text, "Not JSON, hex or base64"))?;
+    decode_bytes(&bytes, depth)
+}
+
+/// Decode a pasted proof into the contents of a `proof.json`.
+///
+/// Accepts raw JSON, JSON envelopes around a proof, and hex or base64 of a JSON or bincode proof.
+pub fn decode_pasted(text: &str) -> Result<Vec<u8>, String> {
+    decode_text(text, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROOF: &str = r#"{"session": {"header": 1}, "substrings": {"openings": 2}}"#;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn value(bytes: &[u8]) -> serde_json::Value {
+        serde_json::from_slice(bytes).unwrap()
+    }
+
+    #[test]
+    fn raw_proof_is_kept_as_is() {
+        let pasted = format!("\n  {}\n", PROOF);
+        assert_eq!(decode_pasted(&pasted).unwrap(), PROOF.as_bytes());
+    }
+
+    #[test]
+    fn proof_in_envelope() {
+        let proof = value(PROOF.as_bytes());
+        let pasted = format!(r#"{{"id": 7, "data": {{"proof": {}}}}}"#, PROOF);
+        assert_eq!(value(&decode_pasted(&pasted).unwrap()), proof);
+
+        let pasted = format!(r#"[{{"proof": "{}"}}]"#, STANDARD.encode(PROOF));
+        assert_eq!(value(&decode_pasted(&pasted).unwrap()), proof);
+    }
+
+    #[test]
+    fn proof_depth_in_envelope() {
+        let pasted = format!(r#"{{"a": {{"b": {{"c": {{"d": {}}}}}}}}}"#, PROOF);
+        assert!(decode_pasted(&pasted).is_ok());
+
+        let pasted = format!(r#"{{"a": {{"b": {{"c": {{"d": {{"e": {}}}}}}}}}}}"#, PROOF);
+        assert_eq!(
+            decode_pasted(&pasted),
+            Err("No proof found in the JSON".to_string())
+        );
+    }
+
+    #[test]
+    fn encoded_proof() {
+        let expected = PROOF.as_bytes();
+        let hex = hex(expected);
+        assert_eq!(decode_pasted(&hex).unwrap(), expected);
+        assert_eq!(decode_pasted(&format!("0x{}", hex)).unwrap(), expected);
+        assert_eq!(decode_pasted(&STANDARD.encode(PROOF)).unwrap(), expected);
+        assert_eq!(
+            decode_pasted(&URL_SAFE_NO_PAD.encode(PROOF)).unwrap(),
+            expected
+        );
+
+        // Line breaks inside the encoding are ignored
+        let (start, end) = hex.split_at(20);
+        assert_eq!(
+            decode_pasted(&format!("{}\n{}", start, end)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(decode_hex(""), None);
+        assert_eq!(decode_hex("0x"), None);
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("éé"), None);
+        assert_eq!(decode_hex("0x0aFf"), Some(vec![0x0a, 0xff]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            decode_pasted("not a proof!"),
+            Err("Not JSON, hex or base64".to_string())
+        );
+        assert!(decode_pasted("{\"session\": ")
+            .unwrap_err()
+            .starts_with("Invalid JSON"));
+        // Base64 of bytes that are neither JSON nor a bincode proof
+        assert!(decode_pasted(&STANDARD.encode([1, 2, 3]))
+            .unwrap_err()
+            .starts_with("Not a bincode proof"));
+    }
+
+    #[test]
+    fn other_file_is_explained() {
+        let session_proof = r#"{"session": {"header": 1}}"#;
+        assert_eq!(
+            decode_pasted(session_proof),
+            Err(crate::artifact::Artifact::SessionProof.explain())
+        );
+    }
+}
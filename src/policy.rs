@@ -0,0 +1,1376 @@
+use std::fmt;
+use std::ops::Range;
+
+use gloo::storage::{LocalStorage, Storage};
+use regex::bytes::Regex;
+use serde::Deserialize;
+
+use crate::claims::json::find_value;
+use crate::request::{check_host, host_name, parse_request, HostCheck, Request};
+use crate::verify::{Transcript, VerifiedProof};
+
+// Local storage key of the policy text
+const POLICY_KEY: &str = "proof_viz.policy";
+
+/// Requirements a verified proof has to meet, e.g. for an integration that only accepts
+/// proofs of one API.
+///
+/// Written as JSON or TOML:
+///
+/// ```toml
+/// name = "Bank balance"
+///
+/// [[rules]]
+/// type = "server_name"
+/// pattern = "api.bank.example"
+///
+/// [[rules]]
+/// type = "time"
+/// max_age = 86400
+///
+/// [[rules]]
+/// type = "request_line"
+/// pattern = "^GET /v1/accounts"
+///
+/// [[rules]]
+/// type = "json"
+/// path = "$.status"
+/// equals = "ok"
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+/// The HTTP message of a transcript
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Message {
+    /// The first request in the sent transcript
+    Request,
+    /// The first response in the received transcript
+    Response,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Request => write!(f, "request"),
+            Message::Response => write!(f, "response"),
+        }
+    }
+}
+
+/// A check on a verified proof. Patterns are regular expressions unless noted otherwise.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Rule {
+    /// The server name matches a name where `*` stands for any part of it, e.g. `*.bank.example`
+    ServerName { pattern: String },
+    /// The notarization time is in a window. Bounds are RFC 3339 dates, `max_age` is in seconds.
+    Time {
+        #[serde(default)]
+        not_before: Option<String>,
+        #[serde(default)]
+        not_after: Option<String>,
+        #[serde(default)]
+        max_age: Option<u64>,
+    },
+    /// The proof verified with one of these Notary keys, given as fingerprints or their prefixes
+    Notary { keys: Vec<String> },
+    /// The request line of the request, e.g. `GET /v1/accounts HTTP/1.1`
+    RequestLine { pattern: String },
+    /// The method of the request, e.g. `GET`
+    Method { method: String },
+    /// The path of the request, without the query string
+    Path { pattern: String },
+    /// A query parameter of the request, percent-decoded. Without `pattern`, the parameter
+    /// only has to be present.
+    Query {
+        name: String,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// The Host header of the request is the server name, or matches a name where `*` stands
+    /// for any part of it
+    Host {
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// The status line of the response, e.g. `HTTP/1.1 200 OK`
+    StatusLine { pattern: String },
+    /// A header value, the header name is case-insensitive
+    Header {
+        message: Message,
+        name: String,
+        pattern: String,
+    },
+    /// The disclosed text of a whole message
+    Text { message: Message, pattern: String },
+    /// A value of the JSON body of the response, at a path like `$.data.items[0]["id"]`.
+    /// Without `equals` and `pattern`, the value only has to exist.
+    Json {
+        path: String,
+        #[serde(default)]
+        equals: Option<serde_json::Value>,
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::ServerName { pattern } => write!(f, "Server name is {}", pattern),
+            Rule::Time {
+                not_before,
+                not_after,
+                max_age,
+            } => {
+                let mut parts = Vec::new();
+                if let Some(not_before) = not_before {
+                    parts.push(format!("not before {}", not_before));
+                }
+                if let Some(not_after) = not_after {
+                    parts.push(format!("not after {}", not_after));
+                }
+                if let Some(max_age) = max_age {
+                    parts.push(format!("at most {} old", format_age(*max_age)));
+                }
+                write!(f, "Notarized {}", parts.join(", "))
+            }
+            Rule::Notary { keys } => write!(f, "Notary key is one of {}", keys.join(", ")),
+            Rule::RequestLine { pattern } => write!(f, "Request line matches /{}/", pattern),
+            Rule::Method { method } => write!(f, "Request method is {}", method),
+            Rule::Path { pattern } => write!(f, "Request path matches /{}/", pattern),
+            Rule::Query { name, pattern } => match pattern {
+                Some(pattern) => write!(f, "Query parameter {} matches /{}/", name, pattern),
+                None => write!(f, "Query has parameter {}", name),
+            },
+            Rule::Host { pattern } => match pattern {
+                Some(pattern) => write!(f, "Host header is {}", pattern),
+                None => write!(f, "Host header is the server name"),
+            },
+            Rule::StatusLine { pattern } => write!(f, "Status line matches /{}/", pattern),
+            Rule::Header {
+                message,
+                name,
+                pattern,
+            } => write!(f, "{} header {} matches /{}/", message, name, pattern),
+            Rule::Text { message, pattern } => write!(f, "{} text matches /{}/", message, pattern),
+            Rule::Json {
+                path,
+                equals,
+                pattern,
+            } => match (equals, pattern) {
+                (Some(equals), _) => write!(f, "Response JSON {} == {}", path, equals),
+                (None, Some(pattern)) => write!(f, "Response JSON {} matches /{}/", path, pattern),
+                (None, None) => write!(f, "Response JSON has {}", path),
+            },
+        }
+    }
+}
+
+/// A duration like `1d 3h 20min`
+fn format_age(seconds: u64) -> String {
+    let parts: Vec<String> = [
+        (seconds / 86400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "min"),
+        (seconds % 60, "s"),
+    ]
+    .into_iter()
+    .filter(|&(count, _)| count > 0)
+    .map(|(count, suffix)| format!("{}{}", count, suffix))
+    .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Result of a rule on a proof
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    /// The disclosed parts of the proof are not enough to decide, because of redactions
+    Undeterminable(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleResult {
+    pub rule: String,
+    pub outcome: Outcome,
+}
+
+impl Policy {
+    /// Parse a policy written as JSON or TOML, and check its patterns, dates and paths
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let policy: Policy = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|err| format!("Invalid JSON policy: {}", err))?
+        } else {
+            toml::from_str(text).map_err(|err| format!("Invalid TOML policy: {}", err))?
+        };
+
+        Self::new(policy.name, policy.rules)
+    }
+
+    /// A policy with rules that were not parsed from text, e.g. entered in a form
+    pub fn new(name: Option<String>, rules: Vec<Rule>) -> Result<Self, String> {
+        for (i, rule) in rules.iter().enumerate() {
+            rule.validate()
+                .map_err(|err| format!("Rule {} ({}): {}", i + 1, rule, err))?;
+        }
+        Ok(Self { name, rules })
+    }
+
+    /// Evaluate every rule against a proof that verified with the Notary key `notary`.
+    /// `now` is in seconds since the Unix epoch.
+    pub fn evaluate(&self, proof: &VerifiedProof, notary: &str, now: u64) -> Vec<RuleResult> {
+        self.rules
+            .iter()
+            .map(|rule| RuleResult {
+                rule: rule.to_string(),
+                outcome: rule.evaluate(proof, notary, now),
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn regex(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|err| format!("Invalid pattern: {}", err))
+}
+
+fn parse_date(date: &str) -> Result<u64, String> {
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map_err(|err| format!("Invalid date {}: {}", date, err))
+        .map(|date| date.timestamp().max(0) as u64)
+}
+
+impl Rule {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Rule::ServerName { .. } => Ok(()),
+            Rule::Time {
+                not_before,
+                not_after,
+                ..
+            } => {
+                for date in [not_before, not_after].into_iter().flatten() {
+                    parse_date(date)?;
+                }
+                Ok(())
+            }
+            Rule::Notary { keys } if keys.iter().any(|key| key.trim().is_empty()) => {
+                Err("Empty Notary key".to_string())
+            }
+            Rule::Notary { .. } | Rule::Method { .. } | Rule::Host { .. } => Ok(()),
+            Rule::RequestLine { pattern }
+            | Rule::StatusLine { pattern }
+            | Rule::Header { pattern, .. }
+            | Rule::Text { pattern, .. }
+            | Rule::Path { pattern }
+            | Rule::Query {
+                pattern: Some(pattern),
+                ..
+            } => regex(pattern).map(|_| ()),
+            Rule::Query { pattern: None, .. } => Ok(()),
+            Rule::Json { path, pattern, .. } => {
+                parse_path(path)?;
+                if let Some(pattern) = pattern {
+                    regex(pattern)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn evaluate(&self, proof: &VerifiedProof, notary: &str, now: u64) -> Outcome {
+        match self {
+            Rule::ServerName { pattern } => {
+                if matches_name(pattern, &proof.server_name) {
+                    Outcome::Pass
+                } else {
+                    Outcome::Fail(format!("The server name is {}", proof.server_name))
+                }
+            }
+            Rule::Time {
+                not_before,
+                not_after,
+                max_age,
+            } => {
+                let bound =
+                    |date: &Option<String>| date.as_deref().and_then(|date| parse_date(date).ok());
+                if let Some(not_before) = bound(not_before) {
+                    if proof.time < not_before {
+                        return Outcome::Fail("Notarized before the window".to_string());
+                    }
+                }
+                if let Some(not_after) = bound(not_after) {
+                    if proof.time > not_after {
+                        return Outcome::Fail("Notarized after the window".to_string());
+                    }
+                }
+                match max_age {
+                    Some(max_age) if now.saturating_sub(proof.time) > *max_age => {
+                        Outcome::Fail(format!(
+                            "Notarized {} ago",
+                            format_age(now.saturating_sub(proof.time))
+                        ))
+                    }
+                    _ => Outcome::Pass,
+                }
+            }
+            Rule::Notary { keys } => {
+                let notary = notary.to_lowercase();
+                if keys
+                    .iter()
+                    .any(|key| notary.starts_with(&key.trim().to_lowercase()))
+                {
+                    Outcome::Pass
+                } else {
+                    Outcome::Fail(format!("The Notary key is {}", notary))
+                }
+            }
+            Rule::RequestLine { pattern } => {
+                start_line_rule(&proof.sent, pattern, Message::Request)
+            }
+            Rule::StatusLine { pattern } => {
+                start_line_rule(&proof.recv, pattern, Message::Response)
+            }
+            Rule::Method { .. } | Rule::Path { .. } | Rule::Query { .. } | Rule::Host { .. } => {
+                match parse_request(&proof.sent) {
+                    Some(request) => request_rule(self, &request, proof),
+                    None => not_http(&proof.sent, Message::Request),
+                }
+            }
+            Rule::Header {
+                message,
+                name,
+                pattern,
+            } => header_rule(transcript(proof, *message), *message, name, pattern),
+            Rule::Text { message, pattern } => {
+                let transcript = transcript(proof, *message);
+                match regex(pattern) {
+                    Ok(regex) => match_disclosed(&regex, transcript, 0..transcript.bytes.len()),
+                    Err(err) => Outcome::Fail(err),
+                }
+            }
+            Rule::Json {
+                path,
+                equals,
+                pattern,
+            } => json_rule(&proof.recv, path, equals.as_ref(), pattern.as_deref()),
+        }
+    }
+}
+
+fn transcript(proof: &VerifiedProof, message: Message) -> &Transcript {
+    match message {
+        Message::Request => &proof.sent,
+        Message::Response => &proof.recv,
+    }
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters. Case-insensitive,
+/// like DNS names.
+pub(crate) fn matches_name(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+pub(crate) fn overlaps(redacted: &[Range<usize>], range: &Range<usize>) -> bool {
+    redacted
+        .iter()
+        .any(|r| r.start < range.end && range.start < r.end)
+}
+
+/// Look for a match of `regex` in `range` of the transcript that only covers disclosed bytes.
+///
+/// Matches that cover redacted bytes are skipped, the redacted bytes were replaced and may
+/// not match in the original.
+fn match_disclosed(regex: &Regex, transcript: &Transcript, range: Range<usize>) -> Outcome {
+    let bytes = &transcript.bytes[range.clone()];
+    let mut start = 0;
+    while start <= bytes.len() {
+        let Some(found) = regex.find_at(bytes, start) else {
+            break;
+        };
+        let found_range = range.start + found.start()..range.start + found.end();
+        if !overlaps(&transcript.redacted, &found_range) {
+            return Outcome::Pass;
+        }
+        start = found.start() + 1;
+    }
+
+    if overlaps(&transcript.redacted, &range) {
+        Outcome::Undeterminable("No match in the disclosed text, it may be redacted".to_string())
+    } else {
+        Outcome::Fail("No match".to_string())
+    }
+}
+
+/// The first capture group of `regex` in the transcript, or the whole match without groups,
+/// that only covers disclosed bytes
+pub(crate) fn capture_disclosed(
+    regex: &Regex,
+    transcript: &Transcript,
+) -> Result<Range<usize>, Outcome> {
+    let bytes = &transcript.bytes;
+    let mut redacted_match = false;
+    let mut start = 0;
+    while start <= bytes.len() {
+        let Some(captures) = regex.captures_at(bytes, start) else {
+            break;
+        };
+        let whole = captures.get(0).map_or(start..start, |found| found.range());
+        let value = captures.get(1).map_or(whole.clone(), |found| found.range());
+        if !overlaps(&transcript.redacted, &value) {
+            return Ok(value);
+        }
+        redacted_match = true;
+        start = whole.start + 1;
+    }
+
+    if redacted_match || !transcript.redacted.is_empty() {
+        Err(Outcome::Undeterminable(
+            "No match in the disclosed text, it may be redacted".to_string(),
+        ))
+    } else {
+        Err(Outcome::Fail("No match".to_string()))
+    }
+}
+
+/// Byte ranges of the parts of an HTTP message
+pub(crate) struct Http {
+    pub start_line: Range<usize>,
+    /// Names and values of the headers
+    pub headers: Vec<(Range<usize>, Range<usize>)>,
+}
+
+fn find(bytes: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| from + index)
+}
+
+/// Split the head of the first HTTP message in `bytes` into its lines
+pub(crate) fn parse_http(bytes: &[u8]) -> Option<Http> {
+    let head_end = find(bytes, b"\r\n\r\n", 0)?;
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < head_end {
+        let end = find(bytes, b"\r\n", start)
+            .unwrap_or(head_end)
+            .min(head_end);
+        lines.push(start..end);
+        start = end + 2;
+    }
+
+    let mut lines = lines.into_iter();
+    let start_line = lines.next()?;
+    let headers = lines
+        .filter_map(|line| {
+            let colon = line.start + bytes[line.clone()].iter().position(|&b| b == b':')?;
+            let mut value = colon + 1..line.end;
+            while value.start < value.end && bytes[value.start].is_ascii_whitespace() {
+                value.start += 1;
+            }
+            while value.start < value.end && bytes[value.end - 1].is_ascii_whitespace() {
+                value.end -= 1;
+            }
+            Some((line.start..colon, value))
+        })
+        .collect();
+    Some(Http {
+        start_line,
+        headers,
+    })
+}
+
+/// Outcome for a transcript that does not start with an HTTP message
+fn not_http(transcript: &Transcript, message: Message) -> Outcome {
+    if transcript.redacted.is_empty() {
+        Outcome::Fail(format!("The {} is not HTTP", message))
+    } else {
+        Outcome::Undeterminable(format!("The {} can not be parsed as HTTP", message))
+    }
+}
+
+fn start_line_rule(transcript: &Transcript, pattern: &str, message: Message) -> Outcome {
+    let regex = match regex(pattern) {
+        Ok(regex) => regex,
+        Err(err) => return Outcome::Fail(err),
+    };
+    match parse_http(&transcript.bytes) {
+        Some(http) => match_disclosed(&regex, transcript, http.start_line),
+        None => not_http(transcript, message),
+    }
+}
+
+/// Evaluate a rule on the method, path, query or Host header of the request
+fn request_rule(rule: &Rule, request: &Request, proof: &VerifiedProof) -> Outcome {
+    match rule {
+        Rule::Method { .. } if request.method.redacted => {
+            Outcome::Undeterminable("The method is redacted".to_string())
+        }
+        Rule::Method { method } if request.method.text == *method => Outcome::Pass,
+        Rule::Method { .. } => Outcome::Fail(format!("The method is {}", request.method.text)),
+        Rule::Path { pattern } => match regex(pattern) {
+            Ok(regex) => match match_disclosed(&regex, &proof.sent, request.path.range.clone()) {
+                Outcome::Fail(_) => Outcome::Fail(format!("The path is {}", request.path.text)),
+                outcome => outcome,
+            },
+            Err(err) => Outcome::Fail(err),
+        },
+        Rule::Query { name, pattern } => {
+            let regex = match pattern.as_deref().map(regex).transpose() {
+                Ok(regex) => regex,
+                Err(err) => return Outcome::Fail(err),
+            };
+            let mut values = Vec::new();
+            let mut redacted_value = false;
+            for (parameter, value) in &request.parameters {
+                if parameter.redacted || parameter.decoded() != *name {
+                    continue;
+                }
+                let Some(regex) = &regex else {
+                    return Outcome::Pass;
+                };
+                match value {
+                    Some(value) if value.redacted => redacted_value = true,
+                    value => {
+                        let value = value
+                            .as_ref()
+                            .map(|value| value.decoded())
+                            .unwrap_or_default();
+                        if regex.is_match(value.as_bytes()) {
+                            return Outcome::Pass;
+                        }
+                        values.push(value);
+                    }
+                }
+            }
+
+            let redacted_name = request
+                .parameters
+                .iter()
+                .any(|(parameter, _)| parameter.redacted);
+            if redacted_value {
+                Outcome::Undeterminable(format!("The value of {} is redacted", name))
+            } else if redacted_name {
+                Outcome::Undeterminable("Some query parameter names are redacted".to_string())
+            } else if values.is_empty() {
+                Outcome::Fail(format!("No query parameter {}", name))
+            } else {
+                Outcome::Fail(format!("{} is {}", name, values.join(", ")))
+            }
+        }
+        Rule::Host { pattern } => {
+            let expected = pattern.as_deref().unwrap_or(&proof.server_name);
+            match (&request.host, check_host(request, &proof.server_name)) {
+                (_, HostCheck::Redacted) => Outcome::Undeterminable(
+                    "The Host header or some header names are redacted".to_string(),
+                ),
+                (_, HostCheck::Missing) => Outcome::Fail("No Host header".to_string()),
+                (Some(host), _) if matches_name(expected, host_name(&host.text)) => Outcome::Pass,
+                (Some(host), _) => Outcome::Fail(format!("The Host header is {}", host.text)),
+                (None, _) => Outcome::Fail("No Host header".to_string()),
+            }
+        }
+        _ => Outcome::Fail("Not a rule on the request".to_string()),
+    }
+}
+
+fn header_rule(transcript: &Transcript, message: Message, name: &str, pattern: &str) -> Outcome {
+    let regex = match regex(pattern) {
+        Ok(regex) => regex,
+        Err(err) => return Outcome::Fail(err),
+    };
+    let Some(http) = parse_http(&transcript.bytes) else {
+        return not_http(transcript, message);
+    };
+
+    let bytes = &transcript.bytes;
+    let mut values = Vec::new();
+    let mut redacted_value = false;
+    for (header_name, value) in &http.headers {
+        if overlaps(&transcript.redacted, header_name)
+            || !bytes[header_name.clone()].eq_ignore_ascii_case(name.as_bytes())
+        {
+            continue;
+        }
+        match match_disclosed(&regex, transcript, value.clone()) {
+            Outcome::Pass => return Outcome::Pass,
+            Outcome::Undeterminable(_) => redacted_value = true,
+            Outcome::Fail(_) => {
+                values.push(String::from_utf8_lossy(&bytes[value.clone()]).to_string())
+            }
+        }
+    }
+
+    let redacted_name = http
+        .headers
+        .iter()
+        .any(|(header_name, _)| overlaps(&transcript.redacted, header_name));
+    if redacted_value {
+        Outcome::Undeterminable(format!("The value of {} is redacted", name))
+    } else if redacted_name {
+        Outcome::Undeterminable(format!("Some header names of the {} are redacted", message))
+    } else if values.is_empty() {
+        Outcome::Fail(format!("No {} header", name))
+    } else {
+        Outcome::Fail(format!("{} is {}", name, values.join(", ")))
+    }
+}
+
+/// The range of the value of the first header `name` of the message that is disclosed
+pub(crate) fn header_value(
+    transcript: &Transcript,
+    message: Message,
+    name: &str,
+) -> Result<Range<usize>, Outcome> {
+    let Some(http) = parse_http(&transcript.bytes) else {
+        return Err(not_http(transcript, message));
+    };
+
+    let mut redacted_value = false;
+    for (header_name, value) in &http.headers {
+        if overlaps(&transcript.redacted, header_name)
+            || !transcript.bytes[header_name.clone()].eq_ignore_ascii_case(name.as_bytes())
+        {
+            continue;
+        }
+        if !overlaps(&transcript.redacted, value) {
+            return Ok(value.clone());
+        }
+        redacted_value = true;
+    }
+
+    let redacted_name = http
+        .headers
+        .iter()
+        .any(|(header_name, _)| overlaps(&transcript.redacted, header_name));
+    Err(if redacted_value {
+        Outcome::Undeterminable(format!("The value of {} is redacted", name))
+    } else if redacted_name {
+        Outcome::Undeterminable(format!("Some header names of the {} are redacted", message))
+    } else {
+        Outcome::Fail(format!("No {} header", name))
+    })
+}
+
+/// A step of a JSON path
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a path like `$.data.items[0]["a key"]`
+pub(crate) fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let invalid = || format!("Invalid JSON path {}", path);
+    let mut rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix("[\"") {
+            let end = after.find("\"]").ok_or_else(invalid)?;
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let index = after[..end].trim().parse().map_err(|_| invalid())?;
+            segments.push(PathSegment::Index(index));
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+fn lookup<'a>(value: &'a serde_json::Value, path: &[PathSegment]) -> Option<&'a serde_json::Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(index) => value.get(index),
+    })
+}
+
+/// The body of the first HTTP message in `bytes`, without chunked transfer encoding
+pub(crate) fn http_body(bytes: &[u8], http: &Http) -> Option<Vec<Range<usize>>> {
+    let body_start = find(bytes, b"\r\n\r\n", 0)? + 4;
+    let header = |name: &str| {
+        http.headers
+            .iter()
+            .find(|(header_name, _)| {
+                bytes[header_name.clone()].eq_ignore_ascii_case(name.as_bytes())
+            })
+            .map(|(_, value)| String::from_utf8_lossy(&bytes[value.clone()]).to_lowercase())
+    };
+
+    if header("transfer-encoding").is_some_and(|value| value.contains("chunked")) {
+        let mut chunks = Vec::new();
+        let mut start = body_start;
+        loop {
+            let line_end = find(bytes, b"\r\n", start)?;
+            let size = String::from_utf8_lossy(&bytes[start..line_end]);
+            let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+            if size == 0 {
+                return Some(chunks);
+            }
+            let chunk = line_end + 2..line_end + 2 + size;
+            if chunk.end > bytes.len() {
+                return None;
+            }
+            start = chunk.end + 2;
+            chunks.push(chunk);
+        }
+    }
+
+    let end = header("content-length")
+        .and_then(|length| length.trim().parse::<usize>().ok())
+        .map_or(bytes.len(), |length| (body_start + length).min(bytes.len()));
+    let body = body_start..end.max(body_start);
+    Some(vec![body])
+}
+
+/// The value at `path` of the JSON body of the response in `transcript`. The error is the
+/// outcome of a rule that needs the value: the value is missing or depends on redacted bytes.
+pub(crate) fn json_value(
+    transcript: &Transcript,
+    path: &[PathSegment],
+) -> Result<serde_json::Value, Outcome> {
+    let Some(body) =
+        parse_http(&transcript.bytes).and_then(|http| http_body(&transcript.bytes, &http))
+    else {
+        return Err(not_http(transcript, Message::Response));
+    };
+    // Whether each byte of the body is redacted
+    let redacted: Vec<bool> = body
+        .iter()
+        .flat_map(|chunk| chunk.clone())
+        .map(|i| transcript.redacted.iter().any(|range| range.contains(&i)))
+        .collect();
+    let body_redacted = redacted.contains(&true);
+
+    // Parse the body with two different fillers for the redacted bytes: a value that is the
+    // same in both does not depend on redacted bytes
+    let fill = |filler: u8| -> Vec<u8> {
+        body.iter()
+            .flat_map(|chunk| chunk.clone())
+            .zip(&redacted)
+            .map(|(i, &redacted)| {
+                if redacted {
+                    filler
+                } else {
+                    transcript.bytes[i]
+                }
+            })
+            .collect()
+    };
+    let filled = fill(b'X');
+    let parse = |json: &[u8]| serde_json::from_slice::<serde_json::Value>(json).ok();
+    let (Some(json), Some(other)) = (parse(&filled), parse(&fill(b'Y'))) else {
+        return Err(if body_redacted {
+            Outcome::Undeterminable(
+                "The JSON body can not be parsed because of redactions".to_string(),
+            )
+        } else {
+            Outcome::Fail("The response body is not JSON".to_string())
+        });
+    };
+
+    let value = match (lookup(&json, path), lookup(&other, path)) {
+        (Some(value), Some(other)) if value == other => value,
+        (Some(_), Some(_)) | (Some(_), None) | (None, Some(_)) => {
+            return Err(Outcome::Undeterminable("The value is redacted".to_string()))
+        }
+        (None, None) if body_redacted => {
+            return Err(Outcome::Undeterminable(
+                "Not found in the disclosed JSON".to_string(),
+            ))
+        }
+        (None, None) => return Err(Outcome::Fail("Not found".to_string())),
+    };
+
+    // Redacted bytes in or after the object that holds the value may hide the end of a string
+    // and a later duplicate key, which would replace the value
+    let holder_start = match path.split_last() {
+        Some((_, holder)) => find_value(&filled, holder).map(|range| range.start),
+        None => Some(0),
+    };
+    let may_be_replaced = match holder_start {
+        Some(start) => redacted[start..].contains(&true),
+        None => body_redacted,
+    };
+    if may_be_replaced {
+        return Err(Outcome::Undeterminable(
+            "Redacted bytes in or after the object of the value may replace it".to_string(),
+        ));
+    }
+    Ok(value.clone())
+}
+
+fn json_rule(
+    transcript: &Transcript,
+    path: &str,
+    equals: Option<&serde_json::Value>,
+    pattern: Option<&str>,
+) -> Outcome {
+    let path = match parse_path(path) {
+        Ok(path) => path,
+        Err(err) => return Outcome::Fail(err),
+    };
+    let value = match json_value(transcript, &path) {
+        Ok(value) => value,
+        Err(outcome) => return outcome,
+    };
+
+    let text = match &value {
+        serde_json::Value::String(text) => text.clone(),
+        value => value.to_string(),
+    };
+    if let Some(equals) = equals {
+        if value != *equals {
+            return Outcome::Fail(format!("The value is {}", value));
+        }
+    }
+    if let Some(pattern) = pattern {
+        match regex(pattern) {
+            Ok(regex) if regex.is_match(text.as_bytes()) => {}
+            Ok(_) => return Outcome::Fail(format!("The value is {}", value)),
+            Err(err) => return Outcome::Fail(err),
+        }
+    }
+    Outcome::Pass
+}
+
+/// The policy text the user entered last
+pub fn load_text() -> Option<String> {
+    LocalStorage::get(POLICY_KEY).ok()
+}
+
+pub fn save_text(text: &str) {
+    if text.trim().is_empty() {
+        LocalStorage::delete(POLICY_KEY);
+    } else {
+        let _ = LocalStorage::set(POLICY_KEY, text);
+    }
+}
+
+#[cfg(test)]
+// Byte ranges are written as arrays of one range
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    /// A response with `body`, where the parts of `body` equal to `redacted` are redacted
+    fn response(body: &str, redacted: &[&str]) -> Transcript {
+        let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+        let mut transcript = Transcript::redacting(body, redacted);
+        transcript.bytes.splice(0..0, head.bytes());
+        for range in &mut transcript.redacted {
+            *range = head.len() + range.start..head.len() + range.end;
+        }
+        transcript
+    }
+
+    fn value(transcript: &Transcript, path: &str) -> Result<serde_json::Value, Outcome> {
+        json_value(transcript, &parse_path(path).unwrap())
+    }
+
+    #[test]
+    fn json_value_disclosed() {
+        let transcript = response(r#"{"status":"ok","data":{"balance":5}}"#, &[]);
+        assert_eq!(value(&transcript, "$.status"), Ok(serde_json::json!("ok")));
+        assert_eq!(
+            value(&transcript, "$.data.balance"),
+            Ok(serde_json::json!(5))
+        );
+        assert_eq!(
+            value(&transcript, "$.missing"),
+            Err(Outcome::Fail("Not found".to_string()))
+        );
+    }
+
+    #[test]
+    fn json_value_redacted_value() {
+        let transcript = response(r#"{"status":"secret"}"#, &["secret"]);
+        assert!(matches!(
+            value(&transcript, "$.status"),
+            Err(Outcome::Undeterminable(_))
+        ));
+    }
+
+    #[test]
+    fn json_value_redaction_after_value_may_hide_duplicate_key() {
+        // The redacted note could be `","status":"bad`, and the last duplicate key wins
+        let transcript = response(
+            r#"{"status":"ok","note":"aaaaaaaaaaaaaaa"}"#,
+            &["aaaaaaaaaaaaaaa"],
+        );
+        assert!(matches!(
+            value(&transcript, "$.status"),
+            Err(Outcome::Undeterminable(_))
+        ));
+
+        // The same in an enclosing object after the object of the value
+        let transcript = response(
+            r#"{"data":{"status":"ok"},"note":"aaaaaaaaaaaaaaa"}"#,
+            &["aaaaaaaaaaaaaaa"],
+        );
+        assert!(matches!(
+            value(&transcript, "$.data.status"),
+            Err(Outcome::Undeterminable(_))
+        ));
+    }
+
+    #[test]
+    fn json_value_redaction_before_object() {
+        // A duplicate key before the value is replaced by the disclosed one
+        let transcript = response(
+            r#"{"note":"aaaaaaaaaaaaaaa","data":{"status":"ok"}}"#,
+            &["aaaaaaaaaaaaaaa"],
+        );
+        assert_eq!(
+            value(&transcript, "$.data.status"),
+            Ok(serde_json::json!("ok"))
+        );
+    }
+
+    #[test]
+    fn json_rule_redacted_duplicate_key() {
+        let transcript = response(
+            r#"{"status":"ok","note":"aaaaaaaaaaaaaaa"}"#,
+            &["aaaaaaaaaaaaaaa"],
+        );
+        let outcome = json_rule(
+            &transcript,
+            "$.status",
+            Some(&serde_json::json!("ok")),
+            None,
+        );
+        assert!(matches!(outcome, Outcome::Undeterminable(_)));
+    }
+
+    const NOTARY: &str = "ab12cd34";
+    // 2023-11-14T22:13:20Z
+    const TIME: u64 = 1_700_000_000;
+
+    /// A proof of `request`, where the parts of `request` equal to `redacted` are redacted
+    fn proof(request: &str, redacted: &[&str]) -> VerifiedProof {
+        VerifiedProof {
+            server_name: "api.bank.example".to_string(),
+            time: TIME,
+            sent: Transcript::redacting(request, redacted),
+            recv: response(r#"{"status":"ok"}"#, &[]),
+            openings: vec![],
+            certificates: vec![],
+        }
+    }
+
+    fn evaluate(rule: Rule, proof: &VerifiedProof) -> Outcome {
+        rule.evaluate(proof, NOTARY, TIME + 60)
+    }
+
+    fn is_undeterminable(outcome: Outcome) -> bool {
+        matches!(outcome, Outcome::Undeterminable(_))
+    }
+
+    const REQUEST: &str = "GET /v1/accounts?id=42&q=a%20b HTTP/1.1\r\nHost: api.bank.example\r\nCookie: secret\r\n\r\n";
+
+    #[test]
+    fn name_wildcards() {
+        assert!(matches_name("api.bank.example", "API.Bank.Example"));
+        assert!(matches_name("*.bank.example", "api.bank.example"));
+        assert!(matches_name("api.*.example", "api.eu.bank.example"));
+        assert!(matches_name("a*b*a", "aba"));
+        assert!(matches_name("*", "anything"));
+        assert!(!matches_name("*.bank.example", "bank.example"));
+        assert!(!matches_name("*.bank.example", "api.bank.example.evil"));
+        assert!(!matches_name("a*b*a", "ab"));
+        assert!(!matches_name("bank.example", "api.bank.example"));
+    }
+
+    #[test]
+    fn path_parsing() {
+        assert_eq!(
+            parse_path(r#"$.data.items[0]["a key"]"#),
+            Ok(vec![
+                PathSegment::Key("data".to_string()),
+                PathSegment::Key("items".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Key("a key".to_string()),
+            ])
+        );
+        assert_eq!(parse_path(" $ "), Ok(vec![]));
+        for path in [
+            "data", "$..a", "$.", "$[x]", "$[-1]", r#"$["a"#, "$.a[1", "$a",
+        ] {
+            assert_eq!(
+                parse_path(path),
+                Err(format!("Invalid JSON path {}", path)),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn chunked_body() {
+        let bytes = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n3;ext=1\r\n:1}\r\n0\r\n\r\n";
+        let http = parse_http(bytes).unwrap();
+        let chunks = http_body(bytes, &http).unwrap();
+        let body: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| bytes[chunk.clone()].to_vec())
+            .collect();
+        assert_eq!(body, b"{\"a\":1}");
+
+        let transcript = Transcript::redacting(std::str::from_utf8(bytes).unwrap(), &[]);
+        assert_eq!(value(&transcript, "$.a"), Ok(serde_json::json!(1)));
+
+        // A chunk that goes past the end of the transcript
+        let bytes = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n9\r\n{}\r\n";
+        assert_eq!(http_body(bytes, &parse_http(bytes).unwrap()), None);
+    }
+
+    #[test]
+    fn content_length_body() {
+        let bytes = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}HTTP/1.1 200 OK";
+        let http = parse_http(bytes).unwrap();
+        assert_eq!(http_body(bytes, &http), Some(vec![38..40]));
+    }
+
+    #[test]
+    fn header_values() {
+        let request = Transcript::redacting(REQUEST, &["secret"]);
+        let host = header_value(&request, Message::Request, "host").unwrap();
+        assert_eq!(&request.bytes[host], b"api.bank.example");
+        assert!(matches!(
+            header_value(&request, Message::Request, "Cookie"),
+            Err(Outcome::Undeterminable(_))
+        ));
+        assert_eq!(
+            header_value(&request, Message::Request, "Accept"),
+            Err(Outcome::Fail("No Accept header".to_string()))
+        );
+
+        // A redacted header name may be the header
+        let request = Transcript::redacting(REQUEST, &["Cookie"]);
+        assert!(matches!(
+            header_value(&request, Message::Request, "Accept"),
+            Err(Outcome::Undeterminable(_))
+        ));
+
+        let not_http = Transcript::redacting("hello", &[]);
+        assert_eq!(
+            header_value(&not_http, Message::Request, "Host"),
+            Err(Outcome::Fail("The request is not HTTP".to_string()))
+        );
+    }
+
+    #[test]
+    fn captures() {
+        let regex = regex(r"id=(\d+)").unwrap();
+        let transcript = Transcript::redacting("id=11 id=22", &[]);
+        assert_eq!(capture_disclosed(&regex, &transcript), Ok(3..5));
+
+        // A redacted match is skipped
+        let transcript = Transcript::redacting("id=11 id=22", &["11"]);
+        assert_eq!(capture_disclosed(&regex, &transcript), Ok(9..11));
+
+        let transcript = Transcript::redacting("id=11", &["11"]);
+        assert!(matches!(
+            capture_disclosed(&regex, &transcript),
+            Err(Outcome::Undeterminable(_))
+        ));
+        let transcript = Transcript::redacting("no ids", &[]);
+        assert_eq!(
+            capture_disclosed(&regex, &transcript),
+            Err(Outcome::Fail("No match".to_string()))
+        );
+    }
+
+    #[test]
+    fn server_name_rule() {
+        let proof = proof(REQUEST, &[]);
+        let rule = |pattern: &str| Rule::ServerName {
+            pattern: pattern.to_string(),
+        };
+        assert_eq!(evaluate(rule("*.bank.example"), &proof), Outcome::Pass);
+        assert_eq!(
+            evaluate(rule("bank.example"), &proof),
+            Outcome::Fail("The server name is api.bank.example".to_string())
+        );
+    }
+
+    #[test]
+    fn time_rule() {
+        let proof = proof(REQUEST, &[]);
+        let rule = |not_before: Option<&str>, not_after: Option<&str>, max_age| Rule::Time {
+            not_before: not_before.map(str::to_string),
+            not_after: not_after.map(str::to_string),
+            max_age,
+        };
+        assert_eq!(
+            evaluate(
+                rule(
+                    Some("2023-11-01T00:00:00Z"),
+                    Some("2023-12-01T00:00:00+01:00"),
+                    Some(60)
+                ),
+                &proof
+            ),
+            Outcome::Pass
+        );
+        assert_eq!(
+            evaluate(rule(Some("2023-12-01T00:00:00Z"), None, None), &proof),
+            Outcome::Fail("Notarized before the window".to_string())
+        );
+        assert_eq!(
+            evaluate(rule(None, Some("2023-11-01T00:00:00Z"), None), &proof),
+            Outcome::Fail("Notarized after the window".to_string())
+        );
+        assert_eq!(
+            evaluate(rule(None, None, Some(59)), &proof),
+            Outcome::Fail("Notarized 1min ago".to_string())
+        );
+        assert!(Policy::new(None, vec![rule(Some("yesterday"), None, None)]).is_err());
+    }
+
+    #[test]
+    fn notary_rule() {
+        let proof = proof(REQUEST, &[]);
+        let rule = |key: &str| Rule::Notary {
+            keys: vec!["ffff".to_string(), key.to_string()],
+        };
+        assert_eq!(evaluate(rule(" AB12 "), &proof), Outcome::Pass);
+        assert_eq!(
+            evaluate(rule("cd34"), &proof),
+            Outcome::Fail(format!("The Notary key is {}", NOTARY))
+        );
+        assert!(Policy::new(None, vec![rule(" ")]).is_err());
+    }
+
+    #[test]
+    fn method_rule() {
+        let rule = || Rule::Method {
+            method: "GET".to_string(),
+        };
+        assert_eq!(evaluate(rule(), &proof(REQUEST, &[])), Outcome::Pass);
+        let post = REQUEST.replacen("GET", "POST", 1);
+        assert_eq!(
+            evaluate(rule(), &proof(&post, &[])),
+            Outcome::Fail("The method is POST".to_string())
+        );
+        assert!(is_undeterminable(evaluate(
+            rule(),
+            &proof(REQUEST, &["GET"])
+        )));
+    }
+
+    #[test]
+    fn path_rule() {
+        let rule = |pattern: &str| Rule::Path {
+            pattern: pattern.to_string(),
+        };
+        assert_eq!(
+            evaluate(rule("^/v1/accounts$"), &proof(REQUEST, &[])),
+            Outcome::Pass
+        );
+        assert_eq!(
+            evaluate(rule("^/v2/"), &proof(REQUEST, &[])),
+            Outcome::Fail("The path is /v1/accounts".to_string())
+        );
+        assert!(is_undeterminable(evaluate(
+            rule("^/v1/accounts$"),
+            &proof(REQUEST, &["accounts"])
+        )));
+        // The disclosed part of the path is enough
+        assert_eq!(
+            evaluate(rule("^/v1/"), &proof(REQUEST, &["accounts"])),
+            Outcome::Pass
+        );
+    }
+
+    #[test]
+    fn query_rule() {
+        let rule = |name: &str, pattern: Option<&str>| Rule::Query {
+            name: name.to_string(),
+            pattern: pattern.map(str::to_string),
+        };
+        let disclosed = proof(REQUEST, &[]);
+        assert_eq!(evaluate(rule("id", None), &disclosed), Outcome::Pass);
+        assert_eq!(
+            evaluate(rule("q", Some("^a b$")), &disclosed),
+            Outcome::Pass
+        );
+        assert_eq!(
+            evaluate(rule("id", Some("^7$")), &disclosed),
+            Outcome::Fail("id is 42".to_string())
+        );
+        assert_eq!(
+            evaluate(rule("page", None), &disclosed),
+            Outcome::Fail("No query parameter page".to_string())
+        );
+
+        let redacted_value = proof(REQUEST, &["42"]);
+        assert!(is_undeterminable(evaluate(
+            rule("id", Some("^42$")),
+            &redacted_value
+        )));
+        // The parameter is there, whatever its value
+        assert_eq!(evaluate(rule("id", None), &redacted_value), Outcome::Pass);
+
+        let redacted_name = proof(REQUEST, &["id"]);
+        assert!(is_undeterminable(evaluate(
+            rule("id", None),
+            &redacted_name
+        )));
+    }
+
+    #[test]
+    fn host_rule() {
+        let rule = |pattern: Option<&str>| Rule::Host {
+            pattern: pattern.map(str::to_string),
+        };
+        let disclosed = proof(REQUEST, &[]);
+        assert_eq!(evaluate(rule(None), &disclosed), Outcome::Pass);
+        assert_eq!(
+            evaluate(rule(Some("*.bank.example")), &disclosed),
+            Outcome::Pass
+        );
+        assert_eq!(
+            evaluate(rule(Some("*.other.example")), &disclosed),
+            Outcome::Fail("The Host header is api.bank.example".to_string())
+        );
+
+        let no_host = proof("GET / HTTP/1.1\r\n\r\n", &[]);
+        assert_eq!(
+            evaluate(rule(None), &no_host),
+            Outcome::Fail("No Host header".to_string())
+        );
+
+        assert!(is_undeterminable(evaluate(
+            rule(None),
+            &proof(REQUEST, &["bank"])
+        )));
+        assert!(is_undeterminable(evaluate(
+            rule(None),
+            &proof(REQUEST, &["Cookie"])
+        )));
+    }
+
+    #[test]
+    fn request_rules_on_other_data() {
+        let rule = Rule::Method {
+            method: "GET".to_string(),
+        };
+        assert_eq!(
+            evaluate(rule.clone(), &proof("hello", &[])),
+            Outcome::Fail("The request is not HTTP".to_string())
+        );
+        assert!(is_undeterminable(evaluate(
+            rule,
+            &proof("GET / HTTP/1.1\r\n\r\n", &["\r\n\r\n"])
+        )));
+    }
+
+    #[test]
+    fn header_and_text_rules() {
+        let header = |name: &str, pattern: &str| Rule::Header {
+            message: Message::Request,
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+        };
+        let disclosed = proof(REQUEST, &[]);
+        assert_eq!(
+            evaluate(header("cookie", "^secret$"), &disclosed),
+            Outcome::Pass
+        );
+        assert_eq!(
+            evaluate(header("Cookie", "^other$"), &disclosed),
+            Outcome::Fail("Cookie is secret".to_string())
+        );
+        assert!(is_undeterminable(evaluate(
+            header("Cookie", "^secret$"),
+            &proof(REQUEST, &["secret"])
+        )));
+
+        let text = |pattern: &str| Rule::Text {
+            message: Message::Request,
+            pattern: pattern.to_string(),
+        };
+        assert_eq!(evaluate(text("Cookie: s"), &disclosed), Outcome::Pass);
+        assert_eq!(
+            evaluate(text("token"), &disclosed),
+            Outcome::Fail("No match".to_string())
+        );
+        assert!(is_undeterminable(evaluate(
+            text("token"),
+            &proof(REQUEST, &["secret"])
+        )));
+    }
+
+    #[test]
+    fn policy_parsing() {
+        let policy =
+            Policy::parse("name = \"Bank\"\n\n[[rules]]\ntype = \"method\"\nmethod = \"GET\"\n")
+                .unwrap();
+        assert_eq!(policy.name.as_deref(), Some("Bank"));
+        assert_eq!(
+            Policy::parse(r#"{"rules": [{"type": "path", "pattern": "^/v1/"}]}"#)
+                .unwrap()
+                .evaluate(&proof(REQUEST, &[]), NOTARY, TIME),
+            [RuleResult {
+                rule: "Request path matches /^/v1//".to_string(),
+                outcome: Outcome::Pass,
+            }]
+        );
+
+        let err = Policy::parse(r#"{"rules": [{"type": "path", "pattern": "("}]}"#).unwrap_err();
+        assert!(
+            err.starts_with("Rule 1 (Request path matches /(/): Invalid pattern"),
+            "{}",
+            err
+        );
+        let err = Policy::parse(r#"{"rules": [{"type": "method", "verb": "GET"}]}"#).unwrap_err();
+        assert!(err.starts_with("Invalid JSON policy"), "{}", err);
+        let err = Policy::parse("[[rules]]\ntype = \"json\"\npath = \"status\"\n").unwrap_err();
+        assert_eq!(
+            err,
+            "Rule 1 (Response JSON has status): Invalid JSON path status"
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::ops::Range;
+
+use regex::bytes::Regex;
+
+use crate::components::redacted_bytes_component::Direction;
+use crate::redaction::segments;
+use crate::verify::{Transcript, VerifiedProof};
+
+/// A kind of secret or personal data that should not be disclosed by accident
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Cookie,
+    SetCookie,
+    Authorization,
+    BearerToken,
+    Jwt,
+    ApiKey,
+    Email,
+    Iban,
+    CardNumber,
+}
+
+impl Kind {
+    /// How to avoid disclosing it in the next proof
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            Kind::Cookie => "Redact the Cookie header value, it lets anyone use the session",
+            Kind::SetCookie => "Redact the Set-Cookie header value, it lets anyone use the session",
+            Kind::Authorization => {
+                "Redact the Authorization header value, it holds the credentials"
+            }
+            Kind::BearerToken => "Redact the token, it grants access to the account",
+            Kind::Jwt => "Redact the token, it grants access and its payload can be decoded",
+            Kind::ApiKey => "Redact the key or secret, it grants access to the account",
+            Kind::Email => "Redact the email address unless the proof is about it",
+            Kind::Iban => "Redact the IBAN unless the proof is about it",
+            Kind::CardNumber => "Redact the card number, keep the last digits at most",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Kind::Cookie => "Cookie header",
+            Kind::SetCookie => "Set-Cookie header",
+            Kind::Authorization => "Authorization header",
+            Kind::BearerToken => "Bearer token",
+            Kind::Jwt => "JWT",
+            Kind::ApiKey => "API key or secret",
+            Kind::Email => "Email address",
+            Kind::Iban => "IBAN",
+            Kind::CardNumber => "Card number",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Disclosed bytes that look like a secret or personal data
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub kind: Kind,
+    pub direction: Direction,
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// Header lines whose values are credentials, the value is the second group
+const HEADERS: &str = r"(?im)^(cookie|set-cookie|authorization|proxy-authorization|x-api-key)[ \t]*:[ \t]*([^\r\n \t][^\r\n]*)";
+const BEARER: &str = r"(?i)\bbearer[ \t]+([A-Za-z0-9._~+/-]+=*)";
+/// Parameters and JSON keys named like secrets, the value is the second group
+const SECRET_PARAMETERS: &str = r#"(?i)\b(api[_-]?key|access[_-]?token|refresh[_-]?token|client[_-]?secret|secret|password|passwd)"?[ \t]*[:=][ \t]*"?([^\s"'&,;}]+)"#;
+const JWT: &str = r"\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*";
+/// Keys of common services, recognizable by their prefix
+const API_KEYS: &str = r"\b(?:(?:sk|pk|rk)_(?:live|test)_[A-Za-z0-9]{10,}|sk-[A-Za-z0-9_-]{20,}|gh[pousr]_[A-Za-z0-9]{30,}|github_pat_[A-Za-z0-9_]{20,}|AKIA[0-9A-Z]{16}|AIza[0-9A-Za-z_-]{35}|xox[abprs]-[A-Za-z0-9-]{10,})";
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+const IBAN: &str = r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]){11,30}\b";
+const CARD_NUMBER: &str = r"\b[2-6](?:[ -]?[0-9]){12,18}\b";
+
+/// Check the IBAN checksum: the number with its first four characters moved to the end is 1
+/// modulo 97
+fn is_iban(text: &str) -> bool {
+    let iban: Vec<char> = text.chars().filter(|c| *c != ' ').collect();
+    if !(15..=34).contains(&iban.len()) {
+        return false;
+    }
+    let mut remainder = 0u32;
+    for c in iban[4..].iter().chain(&iban[..4]) {
+        let Some(value) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// Check the Luhn checksum of a card number
+fn is_card_number(text: &str) -> bool {
+    let digits: Vec<u32> = text.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match i % 2 {
+            0 => digit,
+            _ if digit * 2 > 9 => digit * 2 - 9,
+            _ => digit * 2,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+struct Pattern {
+    kind: Kind,
+    regex: Regex,
+    /// Capture group of the secret, 0 for the whole match
+    group: usize,
+    /// Check of the matched text, for numbers with a checksum
+    check: Option<fn(&str) -> bool>,
+    /// The match is a header line, its kind depends on the header name in the first group
+    header: bool,
+}
+
+/// The patterns to look for, broader ones first so that a token is reported once as part of
+/// its header
+fn patterns() -> Vec<Pattern> {
+    let pattern = |kind, regex: &str, group, check| Pattern {
+        kind,
+        regex: Regex::new(regex).expect("valid privacy pattern"),
+        group,
+        check,
+        header: false,
+    };
+    vec![
+        Pattern {
+            header: true,
+            ..pattern(Kind::Authorization, HEADERS, 2, None)
+        },
+        pattern(Kind::BearerToken, BEARER, 1, None),
+        pattern(Kind::ApiKey, SECRET_PARAMETERS, 2, None),
+        pattern(Kind::Jwt, JWT, 0, None),
+        pattern(Kind::ApiKey, API_KEYS, 0, None),
+        pattern(Kind::Email, EMAIL, 0, None),
+        pattern(Kind::Iban, IBAN, 0, Some(is_iban)),
+        pattern(Kind::CardNumber, CARD_NUMBER, 0, Some(is_card_number)),
+    ]
+}
+
+/// The kind of a credential header from its name
+fn header_kind(name: &[u8]) -> Kind {
+    if name.eq_ignore_ascii_case(b"cookie") {
+        Kind::Cookie
+    } else if name.eq_ignore_ascii_case(b"set-cookie") {
+        Kind::SetCookie
+    } else if name.eq_ignore_ascii_case(b"x-api-key") {
+        Kind::ApiKey
+    } else {
+        Kind::Authorization
+    }
+}
+
+/// Look for secrets in the disclosed bytes of a transcript.
+///
+/// Every disclosed segment is searched on its own, so that a finding never covers redacted
+/// bytes, which were replaced and would match as if they were disclosed.
+fn lint_transcript(
+    patterns: &[Pattern],
+    transcript: &Transcript,
+    direction: Direction,
+) -> Vec<Finding> {
+    let bytes = &transcript.bytes;
+    let disclosed: Vec<Range<usize>> = segments(bytes.len(), &transcript.redacted)
+        .into_iter()
+        .filter(|segment| !segment.redacted)
+        .map(|segment| segment.range)
+        .collect();
+
+    let mut findings: Vec<Finding> = Vec::new();
+    for pattern in patterns {
+        for segment in &disclosed {
+            for captures in pattern.regex.captures_iter(&bytes[segment.clone()]) {
+                let (Some(whole), Some(secret)) = (captures.get(0), captures.get(pattern.group))
+                else {
+                    continue;
+                };
+                let start = segment.start + whole.start();
+                // A line that starts after a redaction is not known to be a header
+                if pattern.header && start > 0 && bytes[start - 1] != b'\n' {
+                    continue;
+                }
+                let range = segment.start + secret.start()..segment.start + secret.end();
+                let text = String::from_utf8_lossy(secret.as_bytes()).to_string();
+                if pattern.check.is_some_and(|check| !check(&text)) {
+                    continue;
+                }
+                let kind = match pattern.header {
+                    true => header_kind(&captures[1]),
+                    false => pattern.kind,
+                };
+                let reported = findings
+                    .iter()
+                    .any(|f| f.range.start <= range.start && range.end <= f.range.end);
+                if !reported {
+                    findings.push(Finding {
+                        kind,
+                        direction: direction.clone(),
+                        range,
+                        text,
+                    });
+                }
+            }
+        }
+    }
+    findings.sort_by_key(|finding| finding.range.start);
+    findings
+}
+
+/// Secrets and personal data in the disclosed bytes of the proof, sent bytes first
+pub fn lint(proof: &VerifiedProof) -> Vec<Finding> {
+    let patterns = patterns();
+    let mut findings = lint_transcript(&patterns, &proof.sent, Direction::Send);
+    findings.extend(lint_transcript(&patterns, &proof.recv, Direction::Received));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_text(text: &str, redacted: &[&str]) -> Vec<(Kind, String)> {
+        let transcript = Transcript::redacting(text, redacted);
+        lint_transcript(&patterns(), &transcript, Direction::Send)
+            .into_iter()
+            .map(|finding| (finding.kind, finding.text))
+            .collect()
+    }
+
+    #[test]
+    fn iban_checksum() {
+        assert!(is_iban("GB82 WEST 1234 5698 7654 32"));
+        assert!(is_iban("DE89370400440532013000"));
+        assert!(!is_iban("DE89370400440532013001"));
+        assert!(!is_iban("DE8937040044"));
+        assert!(!is_iban("DE89-3704-0044-0532-0130-00"));
+    }
+
+    #[test]
+    fn card_number_checksum() {
+        assert!(is_card_number("4111 1111 1111 1111"));
+        assert!(is_card_number("5555-5555-5555-4444"));
+        assert!(!is_card_number("4111 1111 1111 1112"));
+        assert!(!is_card_number("4111 1111 111"));
+    }
+
+    #[test]
+    fn numbers_need_a_valid_checksum() {
+        assert_eq!(
+            lint_text("iban=DE89370400440532013000&card=4111111111111111", &[]),
+            [
+                (Kind::Iban, "DE89370400440532013000".to_string()),
+                (Kind::CardNumber, "4111111111111111".to_string()),
+            ]
+        );
+        assert_eq!(
+            lint_text("iban=DE89370400440532013001&order=4111111111111112", &[]),
+            []
+        );
+    }
+
+    #[test]
+    fn headers_by_name() {
+        let findings = lint_text(
+            "GET / HTTP/1.1\r\nCookie: sid=abc\r\nAuthorization: Bearer tok.en\r\nx-api-key: k1\r\n\r\n",
+            &[],
+        );
+        assert_eq!(
+            findings,
+            [
+                (Kind::Cookie, "sid=abc".to_string()),
+                (Kind::Authorization, "Bearer tok.en".to_string()),
+                (Kind::ApiKey, "k1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn secrets_in_body() {
+        let findings = lint_text(
+            r#"{"password": "hunter2", "contact": "alice@example.com"}"#,
+            &[],
+        );
+        assert_eq!(
+            findings,
+            [
+                (Kind::ApiKey, "hunter2".to_string()),
+                (Kind::Email, "alice@example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn redacted_spans() {
+        // A redacted value is not reported, and the rest of it does not match on its own
+        assert_eq!(
+            lint_text(
+                "Cookie: sid=abc\r\nmail: alice@example.com",
+                &["sid=abc", "alice"]
+            ),
+            []
+        );
+
+        // A line after a redaction may be the rest of another line
+        assert_eq!(
+            lint_text("X-Note: secret\r\nCookie: sid=abc", &["secret\r\n"]),
+            []
+        );
+    }
+}
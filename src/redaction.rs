@@ -0,0 +1,621 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::charset::TranscriptDecoder;
+use crate::components::redacted_bytes_component::Direction;
+
+/// A run of transcript bytes that is either completely disclosed or completely redacted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub range: Range<usize>,
+    pub redacted: bool,
+}
+
+/// Split `0..len` into alternating disclosed and redacted segments.
+///
+/// `redacted_ranges` must be sorted, as returned by `iter_ranges()`. The parts of a range that
+/// overlap an earlier range or lie past `len` are ignored.
+pub fn segments(len: usize, redacted_ranges: &[Range<usize>]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut last_end = 0;
+
+    for range in redacted_ranges {
+        let start = range.start.max(last_end).min(len);
+        let end = range.end.min(len);
+        if start >= end {
+            continue;
+        }
+        if start > last_end {
+            segments.push(Segment {
+                range: last_end..start,
+                redacted: false,
+            });
+        }
+        segments.push(Segment {
+            range: start..end,
+            redacted: true,
+        });
+        last_end = end;
+    }
+
+    // Handle remaining non-redacted part
+    if len > last_end {
+        segments.push(Segment {
+            range: last_end..len,
+            redacted: false,
+        });
+    }
+
+    segments
+}
+
+/// Redacted runs longer than this are collapsed when `RedactionStyle::collapse` is set
+const COLLAPSE_MIN_LEN: usize = 16;
+
+/// How redacted bytes are shown in every view of the transcripts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RedactionStyle {
+    /// Character shown for every redacted byte
+    pub glyph: char,
+    /// Show long redacted runs as `███…███ [1,204 bytes redacted]`
+    pub collapse: bool,
+    /// Show redacted runs as placeholder boxes instead of red text
+    pub boxes: bool,
+}
+
+impl Default for RedactionStyle {
+    fn default() -> Self {
+        Self {
+            glyph: 'X',
+            collapse: false,
+            boxes: false,
+        }
+    }
+}
+
+/// Format a count with thousands separators, e.g. `1,204`
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// A character shown in a view of the transcript
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarkedChar {
+    pub c: char,
+    /// Offset of the transcript byte the character was decoded from, `None` for characters
+    /// that were added by the view (like the indentation of pretty printed JSON)
+    pub offset: Option<usize>,
+    pub redacted: bool,
+}
+
+impl MarkedChar {
+    /// A character that is not part of the transcript
+    pub fn inserted(c: char) -> Self {
+        Self {
+            c,
+            offset: None,
+            redacted: false,
+        }
+    }
+}
+
+/// Decode `range` of the transcript into characters flagged as redacted or not.
+///
+/// Every redacted byte is shown as one glyph, so the redacted parts keep their size in views
+/// that reformat the text. Collapsed runs keep their first and last glyphs at their offsets.
+pub fn marked_chars(
+    bytes: &[u8],
+    decoder: &TranscriptDecoder,
+    range: Range<usize>,
+    redacted_ranges: &[Range<usize>],
+    style: &RedactionStyle,
+) -> Vec<MarkedChar> {
+    let redacted = |offset: usize, c: char| MarkedChar {
+        c,
+        offset: Some(offset),
+        redacted: true,
+    };
+
+    segments(bytes.len(), redacted_ranges)
+        .into_iter()
+        .filter_map(|segment| {
+            let start = segment.range.start.max(range.start);
+            let end = segment.range.end.min(range.end);
+            (start < end).then_some((start..end, segment.redacted))
+        })
+        .flat_map(|(range, is_redacted)| {
+            let chars: Vec<MarkedChar> = if is_redacted
+                && style.collapse
+                && range.len() > COLLAPSE_MIN_LEN
+            {
+                let label = format!(" [{} bytes redacted]", format_count(range.len()));
+                (range.start..range.start + 3)
+                    .map(|offset| redacted(offset, style.glyph))
+                    .chain(std::iter::once(redacted(range.start + 3, '…')))
+                    .chain((range.end - 3..range.end).map(|offset| redacted(offset, style.glyph)))
+                    .chain(label.chars().map(|c| redacted(range.end - 1, c)))
+                    .collect()
+            } else if is_redacted {
+                range.map(|offset| redacted(offset, style.glyph)).collect()
+            } else {
+                decoder
+                    .decode(bytes, range)
+                    .into_iter()
+                    .map(|(c, offset)| MarkedChar {
+                        c,
+                        offset: Some(offset),
+                        redacted: false,
+                    })
+                    .collect()
+            };
+            chars
+        })
+        .collect()
+}
+
+/// A string of marked characters that are either all redacted or all disclosed
+pub struct Run {
+    pub text: String,
+    pub redacted: bool,
+    /// Number of transcript bytes the run was decoded from. Exact for redacted runs, which
+    /// keep the offset of every byte; a multi-byte character at the end of a disclosed run
+    /// only counts its first byte.
+    pub byte_count: usize,
+}
+
+/// Group marked characters back into strings that are either redacted or not.
+pub fn runs(chars: &[MarkedChar]) -> Vec<Run> {
+    let mut runs: Vec<(Run, Option<Range<usize>>)> = Vec::new();
+    for &MarkedChar {
+        c,
+        offset,
+        redacted,
+    } in chars
+    {
+        if !matches!(runs.last(), Some((run, _)) if run.redacted == redacted) {
+            let run = Run {
+                text: String::new(),
+                redacted,
+                byte_count: 0,
+            };
+            runs.push((run, None));
+        }
+        let (run, bytes) = runs.last_mut().expect("a run was just pushed");
+        run.text.push(c);
+        if let Some(offset) = offset {
+            *bytes = Some(match bytes.take() {
+                Some(bytes) => bytes.start.min(offset)..bytes.end.max(offset + 1),
+                None => offset..offset + 1,
+            });
+        }
+    }
+
+    runs.into_iter()
+        .map(|(run, bytes)| Run {
+            byte_count: bytes.map_or(0, |bytes| bytes.len()),
+            ..run
+        })
+        .collect()
+}
+
+/// The transcript ranges of a commitment that the substrings proof opens
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Opening {
+    pub id: String,
+    pub direction: Direction,
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Read the commitment openings of the substrings proof from a `proof.json`.
+///
+/// `SubstringsProof` keeps its openings private, but they are plain data in the proof file.
+/// They are only used to describe the redactions, `SubstringsProof::verify` checks them.
+pub fn read_openings(proof: &serde_json::Value) -> Vec<Opening> {
+    let Some(openings) = proof["substrings"]["openings"].as_object() else {
+        return Vec::new();
+    };
+
+    let mut openings: Vec<Opening> = openings
+        .iter()
+        .filter_map(|(id, opening)| {
+            let info = &opening[0];
+            let direction = match info["direction"].as_str()? {
+                "Sent" => Direction::Send,
+                "Received" => Direction::Received,
+                _ => return None,
+            };
+            let ranges = info["ranges"]
+                .as_array()?
+                .iter()
+                .filter_map(|range| {
+                    Some(range["start"].as_u64()? as usize..range["end"].as_u64()? as usize)
+                })
+                .collect();
+            Some(Opening {
+                id: id.clone(),
+                direction,
+                ranges,
+            })
+        })
+        .collect();
+    openings.sort_by_key(|opening| opening.ranges.first().map(|range| range.start));
+
+    openings
+}
+
+/// An opened range next to a redaction, with the opening it belongs to
+pub type Boundary<'a> = Option<(&'a Opening, Range<usize>)>;
+
+/// The opened ranges right before and right after a redacted range
+pub fn surrounding_openings<'a>(
+    openings: &'a [Opening],
+    redacted: &Range<usize>,
+) -> (Boundary<'a>, Boundary<'a>) {
+    let ranges = || {
+        openings.iter().flat_map(|opening| {
+            opening
+                .ranges
+                .iter()
+                .map(move |range| (opening, range.clone()))
+        })
+    };
+    let before = ranges()
+        .filter(|(_, range)| range.end <= redacted.start)
+        .max_by_key(|(_, range)| range.end);
+    let after = ranges()
+        .filter(|(_, range)| range.start >= redacted.end)
+        .min_by_key(|(_, range)| range.start);
+
+    (before, after)
+}
+
+/// How much of a transcript was disclosed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisclosureStats {
+    pub total: usize,
+    pub redacted: usize,
+    /// Number of redacted ranges
+    pub ranges: usize,
+    /// Length of the largest contiguous redaction
+    pub largest: usize,
+}
+
+impl DisclosureStats {
+    pub fn new(len: usize, redacted_ranges: &[Range<usize>]) -> Self {
+        let redacted: Vec<usize> = segments(len, redacted_ranges)
+            .into_iter()
+            .filter(|segment| segment.redacted)
+            .map(|segment| segment.range.len())
+            .collect();
+        Self {
+            total: len,
+            redacted: redacted.iter().sum(),
+            ranges: redacted.len(),
+            largest: redacted.iter().copied().max().unwrap_or(0),
+        }
+    }
+
+    pub fn disclosed(&self) -> usize {
+        self.total - self.redacted
+    }
+
+    pub fn disclosed_percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            100.0 * self.disclosed() as f64 / self.total as f64
+        }
+    }
+
+    pub fn redacted_percent(&self) -> f64 {
+        100.0 - self.disclosed_percent()
+    }
+}
+
+#[cfg(test)]
+// Redacted ranges are written as arrays of one range
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn segment_ranges(len: usize, redacted_ranges: &[Range<usize>]) -> Vec<(Range<usize>, bool)> {
+        segments(len, redacted_ranges)
+            .into_iter()
+            .map(|segment| (segment.range, segment.redacted))
+            .collect()
+    }
+
+    #[test]
+    fn segments_alternate() {
+        assert_eq!(
+            segment_ranges(10, &[2..4, 6..7]),
+            vec![
+                (0..2, false),
+                (2..4, true),
+                (4..6, false),
+                (6..7, true),
+                (7..10, false)
+            ]
+        );
+        assert_eq!(segment_ranges(3, &[]), vec![(0..3, false)]);
+        assert_eq!(segment_ranges(3, &[0..3]), vec![(0..3, true)]);
+        assert!(segment_ranges(0, &[]).is_empty());
+    }
+
+    #[test]
+    fn segments_of_adjacent_overlapping_and_empty_ranges() {
+        // Adjacent ranges stay separate, like in the proof
+        assert_eq!(
+            segment_ranges(5, &[0..2, 2..4]),
+            vec![(0..2, true), (2..4, true), (4..5, false)]
+        );
+        // The bytes of overlapping ranges are counted once
+        assert_eq!(
+            segment_ranges(6, &[0..3, 2..5, 3..4]),
+            vec![(0..3, true), (3..5, true), (5..6, false)]
+        );
+        assert_eq!(segment_ranges(4, &[2..2]), vec![(0..4, false)]);
+    }
+
+    #[test]
+    fn segments_end_at_len() {
+        assert_eq!(
+            segment_ranges(5, &[3..10, 12..14]),
+            vec![(0..3, false), (3..5, true)]
+        );
+    }
+
+    #[test]
+    fn count_with_separators() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1204), "1,204");
+        assert_eq!(format_count(1234567), "1,234,567");
+    }
+
+    fn chars(
+        bytes: &[u8],
+        redacted_ranges: &[Range<usize>],
+        style: &RedactionStyle,
+    ) -> Vec<MarkedChar> {
+        marked_chars(
+            bytes,
+            &TranscriptDecoder::new(bytes),
+            0..bytes.len(),
+            redacted_ranges,
+            style,
+        )
+    }
+
+    fn text(chars: &[MarkedChar]) -> String {
+        chars.iter().map(|c| c.c).collect()
+    }
+
+    #[test]
+    fn one_glyph_per_redacted_byte() {
+        let style = RedactionStyle {
+            glyph: '█',
+            ..RedactionStyle::default()
+        };
+        let chars = chars("aébc".as_bytes(), &[3..4], &style);
+        assert_eq!(text(&chars), "aé█c");
+        assert_eq!(
+            chars
+                .iter()
+                .map(|c| (c.offset, c.redacted))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(0), false),
+                (Some(1), false),
+                (Some(3), true),
+                (Some(4), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn collapse_long_redactions() {
+        let style = RedactionStyle {
+            collapse: true,
+            ..RedactionStyle::default()
+        };
+        // Multi-byte characters right before and after the redaction keep their offsets
+        let bytes = format!("é{}ü", "s".repeat(2000));
+        let chars = chars(bytes.as_bytes(), &[2..2002], &style);
+        assert_eq!(text(&chars), "éXXX…XXX [2,000 bytes redacted]ü");
+
+        let offsets: Vec<Option<usize>> = chars.iter().map(|c| c.offset).collect();
+        assert_eq!(
+            offsets[..9],
+            [0, 2, 3, 4, 5, 1999, 2000, 2001, 2001].map(Some)
+        );
+        assert_eq!(offsets.last(), Some(&Some(2002)));
+        assert!(chars[1..chars.len() - 1].iter().all(|c| c.redacted));
+        assert!(!chars[0].redacted && !chars[chars.len() - 1].redacted);
+    }
+
+    #[test]
+    fn short_redactions_are_not_collapsed() {
+        let style = RedactionStyle {
+            collapse: true,
+            ..RedactionStyle::default()
+        };
+        let bytes = "s".repeat(COLLAPSE_MIN_LEN);
+        assert_eq!(
+            text(&chars(bytes.as_bytes(), &[0..COLLAPSE_MIN_LEN], &style)),
+            "X".repeat(COLLAPSE_MIN_LEN)
+        );
+    }
+
+    #[test]
+    fn chars_of_a_part() {
+        let bytes = b"abcdef";
+        let chars = marked_chars(
+            bytes,
+            &TranscriptDecoder::new(bytes),
+            2..5,
+            &[1..3, 4..10],
+            &RedactionStyle::default(),
+        );
+        assert_eq!(text(&chars), "XdX");
+        assert_eq!(chars[0].offset, Some(2));
+    }
+
+    #[test]
+    fn runs_count_their_bytes() {
+        let style = RedactionStyle {
+            collapse: true,
+            ..RedactionStyle::default()
+        };
+        let bytes = format!("ab{}cd", "s".repeat(100));
+        let mut chars = chars(bytes.as_bytes(), &[2..102], &style);
+        chars.push(MarkedChar::inserted('\n'));
+        chars.push(MarkedChar::inserted(' '));
+        let runs: Vec<(String, bool, usize)> = runs(&chars)
+            .into_iter()
+            .map(|run| (run.text, run.redacted, run.byte_count))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                ("ab".to_string(), false, 2),
+                ("XXX…XXX [100 bytes redacted]".to_string(), true, 100),
+                ("cd\n ".to_string(), false, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_of_inserted_chars_count_no_bytes() {
+        let runs = runs(&[MarkedChar::inserted(' '), MarkedChar::inserted(' ')]);
+        assert_eq!(runs.len(), 1);
+        assert_eq!((runs[0].text.as_str(), runs[0].byte_count), ("  ", 0));
+    }
+
+    fn proof() -> serde_json::Value {
+        json!({
+            "substrings": {
+                "openings": {
+                    "3": [{ "direction": "Received", "ranges": [{ "start": 0, "end": 10 }] }, {}],
+                    "1": [{ "direction": "Sent", "ranges": [{ "start": 20, "end": 30 }, { "start": 40, "end": 45 }] }, {}],
+                    "2": [{ "direction": "Sent", "ranges": [{ "start": 5, "end": 8 }] }, {}],
+                    "4": [{ "direction": "Unknown", "ranges": [] }, {}]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn openings_of_the_proof() {
+        let openings = read_openings(&proof());
+        let openings: Vec<(&str, Direction, Vec<Range<usize>>)> = openings
+            .iter()
+            .map(|opening| {
+                (
+                    opening.id.as_str(),
+                    opening.direction.clone(),
+                    opening.ranges.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            openings,
+            vec![
+                ("3", Direction::Received, vec![0..10]),
+                ("2", Direction::Send, vec![5..8]),
+                ("1", Direction::Send, vec![20..30, 40..45]),
+            ]
+        );
+
+        assert!(read_openings(&json!({})).is_empty());
+        assert!(read_openings(&json!({ "substrings": { "openings": [] } })).is_empty());
+    }
+
+    #[test]
+    fn openings_around_a_redaction() {
+        let openings: Vec<Opening> = read_openings(&proof())
+            .into_iter()
+            .filter(|opening| opening.direction == Direction::Send)
+            .collect();
+        let ids = |(before, after): (Boundary, Boundary)| {
+            (
+                before.map(|(opening, range)| (opening.id.clone(), range)),
+                after.map(|(opening, range)| (opening.id.clone(), range)),
+            )
+        };
+
+        assert_eq!(
+            ids(surrounding_openings(&openings, &(30..40))),
+            (
+                Some(("1".to_string(), 20..30)),
+                Some(("1".to_string(), 40..45))
+            )
+        );
+        assert_eq!(
+            ids(surrounding_openings(&openings, &(8..20))),
+            (
+                Some(("2".to_string(), 5..8)),
+                Some(("1".to_string(), 20..30))
+            )
+        );
+        assert_eq!(
+            ids(surrounding_openings(&openings, &(0..5))),
+            (None, Some(("2".to_string(), 5..8)))
+        );
+        assert_eq!(
+            ids(surrounding_openings(&openings, &(45..50))),
+            (Some(("1".to_string(), 40..45)), None)
+        );
+    }
+
+    #[test]
+    fn disclosure_stats() {
+        let stats = DisclosureStats::new(200, &[0..10, 10..40, 150..160]);
+        assert_eq!(
+            stats,
+            DisclosureStats {
+                total: 200,
+                redacted: 50,
+                ranges: 3,
+                largest: 30,
+            }
+        );
+        assert_eq!(stats.disclosed(), 150);
+        assert_eq!(stats.disclosed_percent(), 75.0);
+        assert_eq!(stats.redacted_percent(), 25.0);
+    }
+
+    #[test]
+    fn disclosure_stats_of_an_empty_transcript() {
+        let stats = DisclosureStats::new(0, &[]);
+        assert_eq!(stats.disclosed(), 0);
+        assert_eq!(stats.disclosed_percent(), 100.0);
+        assert_eq!(stats.redacted_percent(), 0.0);
+    }
+
+    #[test]
+    fn disclosure_stats_count_bytes_once() {
+        let stats = DisclosureStats::new(10, &[0..4, 2..6, 8..20]);
+        assert_eq!(
+            stats,
+            DisclosureStats {
+                total: 10,
+                redacted: 8,
+                ranges: 3,
+                largest: 4,
+            }
+        );
+        assert_eq!(stats.redacted_percent(), 80.0);
+    }
+}
@@ -0,0 +1,429 @@
+use std::ops::Range;
+
+use web_time::Duration;
+
+use crate::charset::TranscriptDecoder;
+use crate::redaction::{marked_chars, runs, RedactionStyle, Run};
+use crate::verify::{ProofReport, Step, Transcript, VerificationError};
+
+/// Outcome of a verification step
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StepOutcome {
+    Passed,
+    Failed,
+    /// An earlier step failed
+    Skipped,
+}
+
+impl StepOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            StepOutcome::Passed => "passed",
+            StepOutcome::Failed => "failed",
+            StepOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+fn step_outcomes(report: &ProofReport) -> Vec<(Step, StepOutcome)> {
+    let failed = match &report.result {
+        Ok(_) => None,
+        Err(VerificationError::Parse(_) | VerificationError::WrongFile(_)) => Some(Step::Parsing),
+        Err(VerificationError::Session(_)) => Some(Step::Session),
+        Err(VerificationError::Substrings(_)) => Some(Step::Substrings),
+    };
+    Step::ALL
+        .into_iter()
+        .map(|step| {
+            let outcome = match failed {
+                Some(failed) if failed == step => StepOutcome::Failed,
+                Some(failed) if failed.number() < step.number() => StepOutcome::Skipped,
+                _ => StepOutcome::Passed,
+            };
+            (step, outcome)
+        })
+        .collect()
+}
+
+fn time_string(time: u64) -> String {
+    (chrono::DateTime::UNIX_EPOCH + Duration::from_secs(time))
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+/// The transcript as runs of disclosed and redacted text, every redacted byte shown as `glyph`
+fn transcript_runs(transcript: &Transcript, glyph: char) -> Vec<Run> {
+    let style = RedactionStyle {
+        glyph,
+        ..Default::default()
+    };
+    let decoder = TranscriptDecoder::new(&transcript.bytes);
+    let chars = marked_chars(
+        &transcript.bytes,
+        &decoder,
+        0..transcript.bytes.len(),
+        &transcript.redacted,
+        &style,
+    );
+    runs(&chars)
+}
+
+fn transcript_text(transcript: &Transcript, glyph: char) -> String {
+    transcript_runs(transcript, glyph)
+        .into_iter()
+        .map(|run| run.text)
+        .collect()
+}
+
+/// Formats a verification report can be saved in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Json, Format::Markdown, Format::Html];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Markdown => "Markdown",
+            Format::Html => "HTML",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Markdown => "text/markdown",
+            Format::Html => "text/html",
+        }
+    }
+
+    /// File name of a report on the proof file `name`
+    pub fn file_name(self, name: &str) -> String {
+        let stem = name.strip_suffix(".json").unwrap_or(name);
+        let extension = match self {
+            Format::Json => "json",
+            Format::Markdown => "md",
+            Format::Html => "html",
+        };
+        format!("{}.report.{}", stem, extension)
+    }
+
+    /// The report on the proof file `name`
+    pub fn render(self, name: &str, report: &ProofReport) -> String {
+        match self {
+            Format::Json => json(name, report),
+            Format::Markdown => markdown(name, report),
+            Format::Html => html(name, report),
+        }
+    }
+}
+
+fn ranges_json(ranges: &[Range<usize>]) -> serde_json::Value {
+    ranges
+        .iter()
+        .map(|range| serde_json::json!([range.start, range.end]))
+        .collect()
+}
+
+fn transcript_json(transcript: &Transcript) -> serde_json::Value {
+    serde_json::json!({
+        "length": transcript.bytes.len(),
+        "redacted": ranges_json(&transcript.redacted),
+        "text": transcript_text(transcript, RedactionStyle::default().glyph),
+    })
+}
+
+/// Machine-readable report of the verification of the proof file `name`.
+///
+/// Redacted ranges are byte offsets into the transcripts. In `text`, every redacted byte is
+/// shown as `X`.
+fn json(name: &str, report: &ProofReport) -> String {
+    let proof = report.result.as_ref().ok();
+    let json = serde_json::json!({
+        "file": name,
+        "proof_hash": report.proof_hash,
+        "notary": report.notary,
+        "status": report.status().to_string(),
+        "error": report.result.as_ref().err().map(|err| err.to_string()),
+        "steps": step_outcomes(report)
+            .into_iter()
+            .map(|(step, outcome)| serde_json::json!({
+                "step": step.to_string(),
+                "outcome": outcome.as_str(),
+            }))
+            .collect::<Vec<_>>(),
+        "server_name": proof.map(|proof| &proof.server_name),
+        "notarization_time": proof.map(|proof| proof.time),
+        "notarization_time_utc": proof.map(|proof| time_string(proof.time)),
+        "disclosed_percent": proof.map(|proof| proof.disclosed_percent()),
+        "transcripts": proof.map(|proof| serde_json::json!({
+            "sent": transcript_json(&proof.sent),
+            "received": transcript_json(&proof.recv),
+        })),
+    });
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+/// A code fence longer than any run of backticks in `text`
+fn fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Summary of the verification of the proof file `name`, e.g. for a ticket
+fn markdown(name: &str, report: &ProofReport) -> String {
+    let proof = report.result.as_ref().ok();
+    let mut md = format!("# Proof verification report: {}\n\n", markdown_cell(name));
+
+    let mut fields = vec![
+        ("Status", report.status().to_string()),
+        ("Proof hash (SHA-256)", format!("`{}`", report.proof_hash)),
+        ("Notary key fingerprint", format!("`{}`", report.notary)),
+    ];
+    if let Some(proof) = proof {
+        fields.push(("Server name", proof.server_name.clone()));
+        fields.push(("Notarization time", time_string(proof.time)));
+        fields.push(("Disclosed", format!("{:.1}%", proof.disclosed_percent())));
+    }
+    if let Err(err) = &report.result {
+        fields.push(("Error", err.to_string()));
+    }
+    md.push_str("| | |\n|---|---|\n");
+    for (label, value) in fields {
+        md.push_str(&format!("| {} | {} |\n", label, markdown_cell(&value)));
+    }
+
+    md.push_str("\n## Verification steps\n\n");
+    for (step, outcome) in step_outcomes(report) {
+        let icon = match outcome {
+            StepOutcome::Passed => "✅",
+            StepOutcome::Failed => "❌",
+            StepOutcome::Skipped => "⏭️",
+        };
+        md.push_str(&format!("- {} {}: {}\n", icon, step, outcome.as_str()));
+    }
+
+    if let Some(proof) = proof {
+        for (title, transcript) in [("Sent", &proof.sent), ("Received", &proof.recv)] {
+            let text = transcript_text(transcript, RedactionStyle::default().glyph);
+            let fence = fence(&text);
+            md.push_str(&format!(
+                "\n## {} transcript\n\n{} of {} bytes redacted, shown as `X`.\n\n{}\n{}\n{}\n",
+                title,
+                transcript
+                    .redacted
+                    .iter()
+                    .map(|range| range.len())
+                    .sum::<usize>(),
+                transcript.bytes.len(),
+                fence,
+                text,
+                fence
+            ));
+        }
+    }
+    md
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Inline styles of the report, so it can be opened without the app
+const HTML_STYLE: &str = "
+body { background: #18181b; color: white; font-family: ui-sans-serif, system-ui, sans-serif; margin: 0; padding: 1rem; }
+.card { max-width: 64rem; margin: 0 auto; padding: 1rem; background: #3f3f46; border: 1px dashed white; border-radius: 1rem; }
+h1 { font-size: 1.25rem; text-align: center; }
+h2 { font-size: 1rem; }
+.value { background: black; color: white; padding: 1rem; border-radius: 0.375rem; margin: 0.25rem 0 1rem; }
+pre { margin: 0; white-space: pre-wrap; word-break: break-all; }
+.redacted { color: red; }
+.passed { color: #4ade80; }
+.failed { color: #f87171; }
+.skipped { color: #9ca3af; }
+";
+
+/// A standalone HTML page with the report, styled like the proof card. Every redacted byte is
+/// shown as `X`, as in the other formats, whatever the app shows them as.
+fn html(name: &str, report: &ProofReport) -> String {
+    let proof = report.result.as_ref().ok();
+    let value = |label: &str, value: &str| {
+        format!(
+            "<b>{}:</b>\n<div class=\"value\"><pre>{}</pre></div>\n",
+            escape_html(label),
+            escape_html(value)
+        )
+    };
+
+    let mut body = format!("<h1>{}</h1>\n", escape_html(name));
+    body.push_str(&value("Status", &report.status().to_string()));
+    body.push_str(&value("Proof hash (SHA-256)", &report.proof_hash));
+    body.push_str(&value("Notary key fingerprint", &report.notary));
+    if let Some(proof) = proof {
+        body.push_str(&value("Server domain", &proof.server_name));
+        body.push_str(&value("Notarization time", &time_string(proof.time)));
+        body.push_str(&value(
+            "Disclosed",
+            &format!("{:.1}%", proof.disclosed_percent()),
+        ));
+    }
+    if let Err(err) = &report.result {
+        body.push_str(&value("Error", &format!("❌ {}", err)));
+    }
+
+    body.push_str("<h2>Verification steps</h2>\n<ul>\n");
+    for (step, outcome) in step_outcomes(report) {
+        body.push_str(&format!(
+            "<li class=\"{}\">{}: {}</li>\n",
+            outcome.as_str(),
+            escape_html(&step.to_string()),
+            outcome.as_str()
+        ));
+    }
+    body.push_str("</ul>\n");
+
+    if let Some(proof) = proof {
+        for (title, transcript) in [("Sent", &proof.sent), ("Received", &proof.recv)] {
+            let text: String = transcript_runs(transcript, RedactionStyle::default().glyph)
+                .into_iter()
+                .map(|run| {
+                    if run.redacted {
+                        format!("<span class=\"redacted\">{}</span>", escape_html(&run.text))
+                    } else {
+                        escape_html(&run.text)
+                    }
+                })
+                .collect();
+            body.push_str(&format!(
+                "<h2>{} transcript</h2>\n<div class=\"value\"><pre>{}</pre></div>\n",
+                title, text
+            ));
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<div class=\"card\">\n{}</div>\n</body>\n</html>\n",
+        escape_html(&format!("Proof verification report: {}", name)),
+        HTML_STYLE,
+        body
+    )
+}
+
+#[cfg(test)]
+// Redacted ranges are written as arrays of one range
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+    use crate::verify::VerifiedProof;
+
+    fn report(result: Result<VerifiedProof, VerificationError>) -> ProofReport {
+        ProofReport {
+            proof_hash: "ab12".to_string(),
+            notary: "cd34".to_string(),
+            result,
+        }
+    }
+
+    /// A verified proof whose request has its token redacted
+    fn verified() -> ProofReport {
+        let sent = b"GET / HTTP/1.1\r\nToken: secret\r\n\r\n".to_vec();
+        let start = sent.len() - 10;
+        report(Ok(VerifiedProof {
+            server_name: "example.com".to_string(),
+            time: 0,
+            sent: Transcript {
+                bytes: sent,
+                redacted: vec![start..start + 6],
+            },
+            recv: Transcript {
+                bytes: b"HTTP/1.1 200 OK\r\n\r\n```".to_vec(),
+                redacted: vec![],
+            },
+            openings: vec![],
+            certificates: vec![],
+        }))
+    }
+
+    #[test]
+    fn fence_is_longer_than_backtick_runs() {
+        assert_eq!(fence("no backticks"), "```");
+        assert_eq!(fence("a `b` c"), "```");
+        assert_eq!(fence("```rust\n```"), "````");
+        assert_eq!(fence("a ````` b ``"), "``````");
+    }
+
+    #[test]
+    fn json_of_verified_proof() {
+        let json: serde_json::Value =
+            serde_json::from_str(&json("proof.json", &verified())).unwrap();
+        assert_eq!(json["status"], "verified");
+        assert_eq!(json["error"], serde_json::Value::Null);
+        assert_eq!(json["server_name"], "example.com");
+        assert_eq!(json["notarization_time_utc"], "1970-01-01 00:00:00 UTC");
+        assert_eq!(
+            json["transcripts"]["sent"]["redacted"],
+            serde_json::json!([[23, 29]])
+        );
+        assert_eq!(
+            json["transcripts"]["sent"]["text"],
+            "GET / HTTP/1.1\r\nToken: XXXXXX\r\n\r\n"
+        );
+        let outcomes: Vec<&str> = json["steps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|step| step["outcome"].as_str().unwrap())
+            .collect();
+        assert!(outcomes.iter().all(|outcome| *outcome == "passed"));
+    }
+
+    #[test]
+    fn json_of_failed_proof() {
+        let report = report(Err(VerificationError::Session("bad signature".to_string())));
+        let json: serde_json::Value = serde_json::from_str(&json("proof.json", &report)).unwrap();
+        assert_eq!(json["status"], "invalid");
+        assert!(json["error"].as_str().unwrap().contains("bad signature"));
+        assert_eq!(json["transcripts"], serde_json::Value::Null);
+        let outcomes: Vec<&str> = json["steps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|step| step["outcome"].as_str().unwrap())
+            .collect();
+        assert_eq!(outcomes, ["passed", "failed", "skipped"]);
+    }
+
+    #[test]
+    fn markdown_of_verified_proof() {
+        let md = markdown("a|b.json", &verified());
+        assert!(md.starts_with("# Proof verification report: a\\|b.json\n"));
+        assert!(md.contains("| Server name | example.com |"));
+        assert!(md.contains("6 of 33 bytes redacted, shown as `X`."));
+        assert!(md.contains("Token: XXXXXX"));
+        // The received transcript ends with backticks, its fence is longer
+        assert!(md.contains("````\nHTTP/1.1 200 OK\r\n\r\n```\n````\n"));
+    }
+
+    #[test]
+    fn html_escapes_and_uses_fixed_glyph() {
+        let html = html("<b>.json", &verified());
+        assert!(html.contains("<h1>&lt;b&gt;.json</h1>"));
+        assert!(html.contains("<span class=\"redacted\">XXXXXX</span>"));
+    }
+}
@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+use crate::policy::{overlaps, parse_http};
+use crate::verify::Transcript;
+
+/// A part of the request, e.g. its method
+#[derive(Clone, Debug, PartialEq)]
+pub struct Part {
+    pub text: String,
+    pub range: Range<usize>,
+    /// Some of its bytes are redacted, so `text` is not what was sent
+    pub redacted: bool,
+}
+
+impl Part {
+    fn new(transcript: &Transcript, range: Range<usize>) -> Self {
+        Self {
+            text: String::from_utf8_lossy(&transcript.bytes[range.clone()]).to_string(),
+            redacted: overlaps(&transcript.redacted, &range),
+            range,
+        }
+    }
+
+    /// The text with percent-encoded bytes decoded, for query parameters
+    pub fn decoded(&self) -> String {
+        percent_decode(&self.text)
+    }
+}
+
+/// The parts of the first request of the sent transcript that checks are about
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub method: Part,
+    /// The path of the request target, without the query string
+    pub path: Part,
+    /// Names and values of the query parameters
+    pub parameters: Vec<(Part, Option<Part>)>,
+    /// The value of the first Host header whose name is disclosed
+    pub host: Option<Part>,
+    /// Some header names are redacted, a Host header may be hidden
+    pub redacted_header_names: bool,
+}
+
+/// Decode `%xx` escapes and `+`, as in query strings
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let byte = text
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match byte {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Split `range` of the transcript at every `separator`
+fn split(transcript: &Transcript, range: Range<usize>, separator: u8) -> Vec<Range<usize>> {
+    let mut parts = Vec::new();
+    let mut start = range.start;
+    for i in range.clone() {
+        if transcript.bytes[i] == separator {
+            parts.push(start..i);
+            start = i + 1;
+        }
+    }
+    parts.push(start..range.end);
+    parts
+}
+
+/// Parse the request line and the Host header of the first request in the sent transcript
+pub fn parse_request(sent: &Transcript) -> Option<Request> {
+    let bytes = &sent.bytes;
+    let http = parse_http(bytes)?;
+
+    let mut tokens = split(sent, http.start_line.clone(), b' ')
+        .into_iter()
+        .filter(|token| !token.is_empty());
+    let method = tokens.next()?;
+    let target = tokens.next()?;
+
+    let question = bytes[target.clone()].iter().position(|&b| b == b'?');
+    let (path, query) = match question {
+        Some(index) => (
+            target.start..target.start + index,
+            Some(target.start + index + 1..target.end),
+        ),
+        None => (target, None),
+    };
+    let parameters = query
+        .map(|query| {
+            split(sent, query, b'&')
+                .into_iter()
+                .filter(|parameter| !parameter.is_empty())
+                .map(
+                    |parameter| match bytes[parameter.clone()].iter().position(|&b| b == b'=') {
+                        Some(index) => (
+                            Part::new(sent, parameter.start..parameter.start + index),
+                            Some(Part::new(sent, parameter.start + index + 1..parameter.end)),
+                        ),
+                        None => (Part::new(sent, parameter), None),
+                    },
+                )
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let disclosed_name = |name: &Range<usize>| !overlaps(&sent.redacted, name);
+    let host = http
+        .headers
+        .iter()
+        .find(|(name, _)| disclosed_name(name) && bytes[name.clone()].eq_ignore_ascii_case(b"host"))
+        .map(|(_, value)| Part::new(sent, value.clone()));
+
+    Some(Request {
+        method: Part::new(sent, method),
+        path: Part::new(sent, path),
+        parameters,
+        host,
+        redacted_header_names: !http.headers.iter().all(|(name, _)| disclosed_name(name)),
+    })
+}
+
+/// The host name of a Host header value, without the port
+pub fn host_name(host: &str) -> &str {
+    let host = host.trim();
+    // An IPv6 address in brackets has colons of its own
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => host.split(':').next().unwrap_or(host),
+    };
+    name.strip_suffix('.').unwrap_or(name)
+}
+
+/// How the Host header of the request compares to the server name checked against the
+/// certificate
+#[derive(Clone, Debug, PartialEq)]
+pub enum HostCheck {
+    Matches,
+    /// The request was sent to another host than the one the certificate is for
+    Differs(String),
+    /// The Host header is redacted, or header names are and may hide another Host header
+    Redacted,
+    Missing,
+}
+
+pub fn check_host(request: &Request, server_name: &str) -> HostCheck {
+    // A redacted header may be a second Host header, which servers may use instead
+    if request.redacted_header_names {
+        return HostCheck::Redacted;
+    }
+    match &request.host {
+        Some(host) if host.redacted => HostCheck::Redacted,
+        Some(host) if host_name(&host.text).eq_ignore_ascii_case(server_name) => HostCheck::Matches,
+        Some(host) => HostCheck::Differs(host.text.clone()),
+        None => HostCheck::Missing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_check(text: &str, redacted: &[&str]) -> HostCheck {
+        let request = parse_request(&Transcript::redacting(text, redacted)).unwrap();
+        check_host(&request, "example.com")
+    }
+
+    #[test]
+    fn disclosed_host() {
+        assert_eq!(
+            host_check("GET / HTTP/1.1\r\nHost: Example.com:443\r\n\r\n", &[]),
+            HostCheck::Matches
+        );
+        assert_eq!(
+            host_check("GET / HTTP/1.1\r\nHost: evil.com\r\n\r\n", &[]),
+            HostCheck::Differs("evil.com".to_string())
+        );
+        assert_eq!(
+            host_check("GET / HTTP/1.1\r\nAccept: */*\r\n\r\n", &[]),
+            HostCheck::Missing
+        );
+    }
+
+    #[test]
+    fn redacted_host() {
+        assert_eq!(
+            host_check("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n", &["example"]),
+            HostCheck::Redacted
+        );
+    }
+
+    #[test]
+    fn redacted_header_name_may_hide_host() {
+        // The redacted header may be `Host: evil.com`
+        assert_eq!(
+            host_check(
+                "GET / HTTP/1.1\r\nHost: example.com\r\nX-Note: evil.com\r\n\r\n",
+                &["X-Note"]
+            ),
+            HostCheck::Redacted
+        );
+        assert_eq!(
+            host_check("GET / HTTP/1.1\r\nX-Note: a\r\n\r\n", &["X-Note"]),
+            HostCheck::Redacted
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::ops::Range;
+
+/// How the search query is interpreted
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SearchMode {
+    /// UTF-8 text, ASCII letters match regardless of case
+    #[default]
+    Text,
+    /// Hexadecimal bytes, e.g. `48 54 54 50` or `0x48545450`
+    Hex,
+}
+
+/// Bytes to search for, or an error message for an invalid hex query
+pub fn parse_query(query: &str, mode: SearchMode) -> Result<Vec<u8>, String> {
+    match mode {
+        SearchMode::Text => Ok(query.as_bytes().to_vec()),
+        SearchMode::Hex => {
+            let digits: String = query
+                .split_whitespace()
+                .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+                .collect();
+            if let Some(invalid) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid hex digit \"{}\"", invalid));
+            }
+            if digits.len() % 2 != 0 {
+                return Err("Hex query needs an even number of digits".to_string());
+            }
+            (0..digits.len())
+                .step_by(2)
+                // Only ASCII digits are left, so every pair is at char boundaries
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|err| err.to_string()))
+                .collect()
+        }
+    }
+}
+
+/// A match of the query in a transcript
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub range: Range<usize>,
+    /// The match overlaps a redacted range: the disclosed bytes match, but the redacted
+    /// bytes are unknown, so this is only a possible match
+    pub overlaps_redaction: bool,
+}
+
+/// Find the non-overlapping matches of `pattern` in `bytes`.
+///
+/// Redacted bytes match anything, which reports the places where the pattern could be
+/// hidden. Windows that are redacted completely are skipped, they would match everywhere.
+pub fn find_matches(
+    bytes: &[u8],
+    redacted_ranges: &[Range<usize>],
+    pattern: &[u8],
+    mode: SearchMode,
+) -> Vec<Match> {
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return Vec::new();
+    }
+
+    let mut redacted = vec![false; bytes.len()];
+    for range in redacted_ranges {
+        for flag in &mut redacted[range.start.min(bytes.len())..range.end.min(bytes.len())] {
+            *flag = true;
+        }
+    }
+
+    let equal = |a: u8, b: u8| match mode {
+        SearchMode::Text => a.eq_ignore_ascii_case(&b),
+        SearchMode::Hex => a == b,
+    };
+
+    let is_match = |window: &Range<usize>| {
+        window
+            .clone()
+            .zip(pattern)
+            .all(|(pos, &expected)| redacted[pos] || equal(bytes[pos], expected))
+    };
+    let redacted_count =
+        |window: &Range<usize>| redacted[window.clone()].iter().filter(|&&r| r).count();
+
+    // Disclosed matches first, so a possible match never hides a certain one
+    let mut matches: Vec<Match> = Vec::new();
+    for overlaps_redaction in [false, true] {
+        // The matches of the first pass, in start order, and the first of them that may still
+        // overlap a window. Matches of the same pass never overlap, the search goes on after
+        // each of them.
+        let earlier = matches.len();
+        let mut next = 0;
+        let mut start = 0;
+        while start + pattern.len() <= bytes.len() {
+            let window = start..start + pattern.len();
+            let count = redacted_count(&window);
+            let wanted = if overlaps_redaction {
+                count > 0 && count < pattern.len()
+            } else {
+                count == 0
+            };
+            while next < earlier && matches[next].range.end <= window.start {
+                next += 1;
+            }
+            let overlaps_match = next < earlier && matches[next].range.start < window.end;
+
+            if wanted && is_match(&window) && !overlaps_match {
+                start = window.end;
+                matches.push(Match {
+                    range: window,
+                    overlaps_redaction,
+                });
+            } else {
+                start += 1;
+            }
+        }
+    }
+    matches.sort_by_key(|m| m.range.start);
+
+    matches
+}
+
+#[cfg(test)]
+// Redacted ranges are written as arrays of one range
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_text_query() {
+        assert_eq!(
+            parse_query("GET /", SearchMode::Text),
+            Ok(b"GET /".to_vec())
+        );
+    }
+
+    #[test]
+    fn parse_hex_query() {
+        assert_eq!(
+            parse_query("48 54 0x5450", SearchMode::Hex),
+            Ok(b"HTTP".to_vec())
+        );
+        assert_eq!(parse_query("0X0a", SearchMode::Hex), Ok(vec![0x0a]));
+        assert!(parse_query("485", SearchMode::Hex).is_err());
+        assert!(parse_query("4g", SearchMode::Hex).is_err());
+    }
+
+    #[test]
+    fn parse_hex_query_non_ascii() {
+        // An even number of bytes, with `é` across a pair of digits
+        assert!(parse_query("aéb", SearchMode::Hex).is_err());
+        assert!(parse_query("éé", SearchMode::Hex).is_err());
+    }
+
+    fn ranges(matches: &[Match]) -> Vec<(Range<usize>, bool)> {
+        matches
+            .iter()
+            .map(|m| (m.range.clone(), m.overlaps_redaction))
+            .collect()
+    }
+
+    #[test]
+    fn find_text_ignores_ascii_case() {
+        let matches = find_matches(b"Host: host", &[], b"HOST", SearchMode::Text);
+        assert_eq!(ranges(&matches), vec![(0..4, false), (6..10, false)]);
+        assert!(find_matches(b"Host", &[], b"HOST", SearchMode::Hex).is_empty());
+    }
+
+    #[test]
+    fn find_non_overlapping() {
+        let matches = find_matches(b"aaaaa", &[], b"aa", SearchMode::Text);
+        assert_eq!(ranges(&matches), vec![(0..2, false), (2..4, false)]);
+    }
+
+    #[test]
+    fn find_possible_matches_in_redactions() {
+        // `XX` is redacted, so `abXX` could be `abcd`
+        let matches = find_matches(b"abXX abcd", &[2..4], b"abcd", SearchMode::Text);
+        assert_eq!(ranges(&matches), vec![(0..4, true), (5..9, false)]);
+
+        // Windows that are redacted completely are not matches
+        assert!(find_matches(b"XXXX", &[0..4], b"ab", SearchMode::Text).is_empty());
+    }
+
+    #[test]
+    fn disclosed_match_wins_over_possible_match() {
+        // `XX` is redacted completely, so only the disclosed `ab` is a match
+        let matches = find_matches(b"XXab", &[0..2], b"ab", SearchMode::Text);
+        assert_eq!(ranges(&matches), vec![(2..4, false)]);
+
+        // `Xa` could be `aa`, but it overlaps the disclosed `aa`, which is the one returned
+        let matches = find_matches(b"Xaa", &[0..1], b"aa", SearchMode::Text);
+        assert_eq!(ranges(&matches), vec![(1..3, false)]);
+    }
+
+    #[test]
+    fn find_nothing() {
+        assert!(find_matches(b"abc", &[], b"", SearchMode::Text).is_empty());
+        assert!(find_matches(b"ab", &[], b"abc", SearchMode::Text).is_empty());
+    }
+}
@@ -0,0 +1,418 @@
+use regex::bytes::Regex;
+use web_time::Duration;
+
+use crate::compare::Candidate;
+use crate::export::csv;
+use crate::policy::{capture_disclosed, json_value, parse_path, regex, Outcome, PathSegment};
+use crate::verify::Transcript;
+
+/// How a value is found in the response of a proof
+#[derive(Clone, Debug)]
+pub enum Extractor {
+    /// A value of the JSON body, at a path like `$.data.balance`
+    Json(Vec<PathSegment>),
+    /// The first capture group of a regular expression, or the whole match without groups
+    Regex(Regex),
+}
+
+impl Extractor {
+    pub fn json(path: &str) -> Result<Self, String> {
+        parse_path(path).map(Extractor::Json)
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, String> {
+        regex(pattern).map(Extractor::Regex)
+    }
+
+    fn extract(&self, transcript: &Transcript) -> Sample {
+        match self {
+            Extractor::Json(path) => match json_value(transcript, path) {
+                Ok(serde_json::Value::String(text)) => Sample::value(text),
+                Ok(value) => Sample::value(value.to_string()),
+                Err(Outcome::Undeterminable(reason)) => Sample::Redacted(reason),
+                Err(Outcome::Fail(reason)) => Sample::Missing(reason),
+                Err(Outcome::Pass) => Sample::Missing("Not found".to_string()),
+            },
+            Extractor::Regex(regex) => match capture_disclosed(regex, transcript) {
+                Ok(range) => {
+                    Sample::value(String::from_utf8_lossy(&transcript.bytes[range]).to_string())
+                }
+                Err(Outcome::Undeterminable(reason)) => Sample::Redacted(reason),
+                Err(Outcome::Fail(reason)) => Sample::Missing(reason),
+                Err(Outcome::Pass) => Sample::Missing("No match".to_string()),
+            },
+        }
+    }
+}
+
+/// The value extracted from one proof
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sample {
+    Value {
+        text: String,
+        /// The value as a number, if it is one, to plot it
+        number: Option<f64>,
+    },
+    /// The value depends on redacted bytes
+    Redacted(String),
+    Missing(String),
+    /// The proof did not verify
+    Failed(String),
+}
+
+impl Sample {
+    fn value(text: String) -> Self {
+        Sample::Value {
+            number: parse_number(&text),
+            text,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Sample::Value { .. } => "value",
+            Sample::Redacted(_) => "redacted",
+            Sample::Missing(_) => "missing",
+            Sample::Failed(_) => "failed",
+        }
+    }
+}
+
+/// A number like `1234.5`, `"1,234.50"` or `1_234`.
+///
+/// Commas are only read as thousands separators, in groups of three digits before the decimal
+/// point. Text like `1.234,50` or `1,5` is not a number, rather than a number read wrongly.
+fn parse_number(text: &str) -> Option<f64> {
+    let text: String = text
+        .trim()
+        .trim_matches('"')
+        .chars()
+        .filter(|c| !matches!(c, '_' | ' '))
+        .collect();
+    let (integer, fraction) = match text.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (text.as_str(), ""),
+    };
+    if fraction.contains(',') {
+        return None;
+    }
+    if integer.contains(',') {
+        let digits = |group: &str| group.bytes().all(|b| b.is_ascii_digit());
+        let mut groups = integer.split(',');
+        let first = groups.next()?.trim_start_matches(['-', '+']);
+        if !(1..=3).contains(&first.len())
+            || !digits(first)
+            || !groups.all(|group| group.len() == 3 && digits(group))
+        {
+            return None;
+        }
+    }
+    text.replace(',', "")
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+/// The value extracted from a proof file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    /// Id of the proof card
+    pub id: usize,
+    pub name: String,
+    pub proof_hash: String,
+    /// Notarization time, in seconds since the Unix epoch, unless the proof did not verify
+    pub time: Option<u64>,
+    pub sample: Sample,
+}
+
+impl Point {
+    pub fn time_string(&self) -> String {
+        self.time
+            .map(|time| {
+                (chrono::DateTime::UNIX_EPOCH + Duration::from_secs(time))
+                    .format("%Y-%m-%d %H:%M:%S UTC")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A value of the responses of one domain over notarization time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub server_name: String,
+    /// The proofs of the domain in notarization order, then the proofs that did not verify
+    pub points: Vec<Point>,
+    /// Proofs that did not verify and whose file name does not name the domain, so they may be
+    /// of any domain
+    pub unattributed: usize,
+}
+
+const HEADER: [&str; 7] = [
+    "file",
+    "proof_hash",
+    "notarization_time",
+    "notarization_time_utc",
+    "status",
+    "value",
+    "reason",
+];
+
+impl Series {
+    /// Extract a value from the responses of the verified proofs of `server_name`, and list
+    /// the proofs that did not verify whose file name names the domain. The server name of a
+    /// proof that did not verify is not known.
+    pub fn extract(extractor: &Extractor, server_name: &str, proofs: &[Candidate]) -> Self {
+        let domain = server_name.to_lowercase();
+        let mut unattributed = 0;
+        let mut points: Vec<Point> = proofs
+            .iter()
+            .filter_map(|Candidate { id, name, report }| {
+                let sample = match &report.result {
+                    Ok(proof) if proof.server_name.eq_ignore_ascii_case(server_name) => {
+                        extractor.extract(&proof.recv)
+                    }
+                    Ok(_) => return None,
+                    Err(err) if name.to_lowercase().contains(&domain) => {
+                        Sample::Failed(err.to_string())
+                    }
+                    Err(_) => {
+                        unattributed += 1;
+                        return None;
+                    }
+                };
+                Some(Point {
+                    id: *id,
+                    name: name.clone(),
+                    proof_hash: report.proof_hash.clone(),
+                    time: report.result.as_ref().ok().map(|proof| proof.time),
+                    sample,
+                })
+            })
+            .collect();
+        points.sort_by_key(|point| (point.time.is_none(), point.time));
+        Self {
+            server_name: server_name.to_string(),
+            points,
+            unattributed,
+        }
+    }
+
+    /// Notarization times and numbers of the points that can be plotted
+    pub fn plotted(&self) -> Vec<(u64, f64)> {
+        self.points
+            .iter()
+            .filter_map(|point| match (&point.sample, point.time) {
+                (
+                    Sample::Value {
+                        number: Some(number),
+                        ..
+                    },
+                    Some(time),
+                ) => Some((time, *number)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// One line per proof, with the value or why there is none
+    pub fn csv(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .points
+            .iter()
+            .map(|point| {
+                let (value, reason) = match &point.sample {
+                    Sample::Value { text, .. } => (text.clone(), String::new()),
+                    Sample::Redacted(reason) | Sample::Missing(reason) | Sample::Failed(reason) => {
+                        (String::new(), reason.clone())
+                    }
+                };
+                vec![
+                    point.name.clone(),
+                    point.proof_hash.clone(),
+                    point.time.map(|time| time.to_string()).unwrap_or_default(),
+                    point.time_string(),
+                    point.sample.kind().to_string(),
+                    value,
+                    reason,
+                ]
+            })
+            .collect();
+        csv(&HEADER, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::verify::{ProofReport, VerificationError, VerifiedProof};
+
+    fn candidate(
+        id: usize,
+        name: &str,
+        result: Result<VerifiedProof, VerificationError>,
+    ) -> Candidate {
+        Candidate {
+            id,
+            name: name.to_string(),
+            report: Rc::new(ProofReport {
+                proof_hash: format!("hash{}", id),
+                notary: "ab12".to_string(),
+                result,
+            }),
+        }
+    }
+
+    fn verified(
+        server_name: &str,
+        time: u64,
+        body: &str,
+        redacted: &[&str],
+    ) -> Result<VerifiedProof, VerificationError> {
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        Ok(VerifiedProof {
+            server_name: server_name.to_string(),
+            time,
+            sent: Transcript::redacting("GET / HTTP/1.1\r\n\r\n", &[]),
+            recv: Transcript::redacting(&response, redacted),
+            openings: vec![],
+            certificates: vec![],
+        })
+    }
+
+    fn failed() -> Result<VerifiedProof, VerificationError> {
+        Err(VerificationError::Session("bad signature".to_string()))
+    }
+
+    fn proofs() -> Vec<Candidate> {
+        vec![
+            candidate(0, "bank.example-failed.json", failed()),
+            candidate(
+                1,
+                "later.json",
+                verified("bank.example", 200, r#"{"balance":"1,234.50"}"#, &[]),
+            ),
+            candidate(
+                2,
+                "other.json",
+                verified("other.example", 50, r#"{"balance":1}"#, &[]),
+            ),
+            candidate(
+                3,
+                "earlier.json",
+                verified("Bank.Example", 100, r#"{"balance":7}"#, &[]),
+            ),
+            candidate(
+                4,
+                "redacted.json",
+                verified("bank.example", 300, r#"{"balance":99}"#, &["99"]),
+            ),
+            candidate(
+                5,
+                "missing.json",
+                verified("bank.example", 150, r#"{"total":5}"#, &[]),
+            ),
+            candidate(6, "unknown.json", failed()),
+        ]
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number("1234.5"), Some(1234.5));
+        assert_eq!(parse_number("\"1,234.50\""), Some(1234.5));
+        assert_eq!(parse_number("-1,234,567"), Some(-1234567.0));
+        assert_eq!(parse_number("1_234"), Some(1234.0));
+        assert_eq!(parse_number(" 42 "), Some(42.0));
+        assert_eq!(parse_number("1.234,50"), None);
+        assert_eq!(parse_number("1,5"), None);
+        assert_eq!(parse_number("12,34"), None);
+        assert_eq!(parse_number("1234,567"), None);
+        assert_eq!(parse_number("inf"), None);
+        assert_eq!(parse_number("ok"), None);
+    }
+
+    #[test]
+    fn points_in_notarization_order() {
+        let series = Series::extract(
+            &Extractor::json("$.balance").unwrap(),
+            "bank.example",
+            &proofs(),
+        );
+        let names: Vec<&str> = series
+            .points
+            .iter()
+            .map(|point| point.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "earlier.json",
+                "missing.json",
+                "later.json",
+                "redacted.json",
+                "bank.example-failed.json"
+            ]
+        );
+        assert_eq!(series.plotted(), [(100, 7.0), (200, 1234.5)]);
+        // Only the failed proof whose file name names the domain is listed
+        assert_eq!(series.unattributed, 1);
+    }
+
+    #[test]
+    fn samples() {
+        let series = Series::extract(
+            &Extractor::json("$.balance").unwrap(),
+            "bank.example",
+            &proofs(),
+        );
+        let kinds: Vec<&str> = series
+            .points
+            .iter()
+            .map(|point| point.sample.kind())
+            .collect();
+        assert_eq!(kinds, ["value", "missing", "value", "redacted", "failed"]);
+        assert_eq!(
+            series.points[1].sample,
+            Sample::Missing("Not found".to_string())
+        );
+        assert_eq!(
+            series.points[4].sample,
+            Sample::Failed("bad signature".to_string())
+        );
+
+        let series = Series::extract(
+            &Extractor::regex(r#""balance":(\d+)"#).unwrap(),
+            "bank.example",
+            &proofs(),
+        );
+        let kinds: Vec<&str> = series
+            .points
+            .iter()
+            .map(|point| point.sample.kind())
+            .collect();
+        assert_eq!(kinds, ["value", "missing", "missing", "redacted", "failed"]);
+    }
+
+    #[test]
+    fn csv_lines() {
+        let proofs = proofs();
+        let series = Series::extract(
+            &Extractor::json("$.balance").unwrap(),
+            "bank.example",
+            &proofs[..4],
+        );
+        assert_eq!(
+            series.csv(),
+            "file,proof_hash,notarization_time,notarization_time_utc,status,value,reason\r\n\
+             earlier.json,hash3,100,1970-01-01 00:01:40 UTC,value,7,\r\n\
+             later.json,hash1,200,1970-01-01 00:03:20 UTC,value,\"1,234.50\",\r\n\
+             bank.example-failed.json,hash0,,,failed,,bad signature\r\n"
+        );
+    }
+}
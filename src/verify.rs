@@ -0,0 +1,332 @@
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use elliptic_curve::sec1::ToEncodedPoint;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tlsn_core::proof::{SessionProof, TlsProof};
+
+use crate::artifact::{identify, Artifact};
+use crate::certificate::{read_certificates, CertificateInfo};
+use crate::components::redacted_bytes_component::Direction;
+use crate::redaction::{read_openings, Opening};
+
+// Redacted bytes are replaced with this byte, so the transcripts can still be parsed as HTTP
+const REDACTED_BYTE: u8 = b'X';
+
+/// A verified transcript
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// The transcript, with the bytes the Prover chose not to disclose replaced
+    pub bytes: Vec<u8>,
+    /// Sorted ranges of the bytes the Prover chose not to disclose
+    pub redacted: Vec<Range<usize>>,
+}
+
+#[cfg(test)]
+impl Transcript {
+    /// `text` with the parts equal to `redacted` replaced, as verification leaves them
+    pub(crate) fn redacting(text: &str, redacted: &[&str]) -> Self {
+        let mut bytes = text.as_bytes().to_vec();
+        let mut ranges: Vec<Range<usize>> = redacted
+            .iter()
+            .map(|part| {
+                let start = text.find(part).expect("redacted part of the text");
+                start..start + part.len()
+            })
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        for range in &ranges {
+            bytes[range.clone()].fill(REDACTED_BYTE);
+        }
+        Self {
+            bytes,
+            redacted: ranges,
+        }
+    }
+}
+
+/// The contents of a proof that verified successfully
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedProof {
+    /// The server name, checked against the certificate chain shared in the TLS handshake
+    pub server_name: String,
+    /// The time at which the session was recorded, in seconds since the Unix epoch
+    pub time: u64,
+    pub sent: Transcript,
+    pub recv: Transcript,
+    /// Commitment openings of the substrings proof, to show where the redactions are
+    pub openings: Vec<Opening>,
+    /// The server certificate chain, leaf first
+    pub certificates: Vec<CertificateInfo>,
+}
+
+impl VerifiedProof {
+    pub fn transcript(&self, direction: &Direction) -> &Transcript {
+        match direction {
+            Direction::Send => &self.sent,
+            Direction::Received => &self.recv,
+        }
+    }
+
+    pub fn openings(&self, direction: &Direction) -> Vec<Opening> {
+        self.openings
+            .iter()
+            .filter(|opening| opening.direction == *direction)
+            .cloned()
+            .collect()
+    }
+
+    /// Percentage of the bytes of both transcripts that were disclosed
+    pub fn disclosed_percent(&self) -> f64 {
+        let total = self.sent.bytes.len() + self.recv.bytes.len();
+        let redacted: usize = [&self.sent, &self.recv]
+            .iter()
+            .flat_map(|transcript| transcript.redacted.iter())
+            .map(|range| range.len())
+            .sum();
+        if total == 0 {
+            100.0
+        } else {
+            100.0 * (total - redacted) as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerificationError {
+    /// The file is not a `TlsProof`
+    Parse(String),
+    /// The file is another TLSNotary file than a proof
+    WrongFile(Artifact),
+    /// The session proof does not verify against the Notary's public key
+    Session(String),
+    /// The substrings proof does not match the session header
+    Substrings(String),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationError::Parse(err) => write!(f, "Parsing failed {}", err),
+            VerificationError::WrongFile(artifact) => write!(f, "{}", artifact.explain()),
+            VerificationError::Session(err) => write!(f, "{}", err),
+            VerificationError::Substrings(err) => {
+                write!(f, "Substrings proof verification failed: {}", err)
+            }
+        }
+    }
+}
+
+/// Verification status of a proof file, as shown in the dashboard
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Verified,
+    /// The proof does not verify
+    Invalid,
+    /// The file could not be parsed as a proof
+    Unreadable,
+}
+
+impl Status {
+    pub const ALL: [Status; 3] = [Status::Verified, Status::Invalid, Status::Unreadable];
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Verified => write!(f, "verified"),
+            Status::Invalid => write!(f, "invalid"),
+            Status::Unreadable => write!(f, "unreadable"),
+        }
+    }
+}
+
+/// Outcome of checking a proof file against a Notary public key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofReport {
+    /// SHA-256 of the proof file, hex encoded
+    pub proof_hash: String,
+    /// Fingerprint of the Notary public key the proof was checked against
+    pub notary: String,
+    pub result: Result<VerifiedProof, VerificationError>,
+}
+
+impl ProofReport {
+    pub fn status(&self) -> Status {
+        match &self.result {
+            Ok(_) => Status::Verified,
+            Err(VerificationError::Parse(_) | VerificationError::WrongFile(_)) => {
+                Status::Unreadable
+            }
+            Err(_) => Status::Invalid,
+        }
+    }
+}
+
+/// Steps of the verification of a proof file
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Step {
+    Parsing,
+    Session,
+    Substrings,
+}
+
+impl Step {
+    pub const COUNT: usize = 3;
+    pub const ALL: [Step; Step::COUNT] = [Step::Parsing, Step::Session, Step::Substrings];
+
+    /// Position of the step, starting at 1
+    pub fn number(self) -> usize {
+        match self {
+            Step::Parsing => 1,
+            Step::Session => 2,
+            Step::Substrings => 3,
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Step::Parsing => write!(f, "parsing the proof"),
+            Step::Session => write!(f, "checking the Notary signature and server certificate"),
+            Step::Substrings => write!(f, "checking the transcript commitments"),
+        }
+    }
+}
+
+/// Where a dropped file is in the pipeline
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileState {
+    Loading,
+    /// Waiting for the verification of other files to finish
+    Queued,
+    Verifying(Step),
+    /// Verified against the Notary public key, successfully or not
+    Done(Rc<ProofReport>),
+    /// The file could not be read, or is not a proof at all
+    Failed(String),
+    Cancelled,
+}
+
+impl FileState {
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            FileState::Loading | FileState::Queued | FileState::Verifying(_)
+        )
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// SHA-256 of a file, hex encoded
+pub fn content_hash(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+/// A Notary key that came with the proof, from a link or an offline bundle, rather than from
+/// the user. Anyone can sign a proof with their own key, so it is only as trustworthy as the key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SuppliedKey {
+    pub fingerprint: String,
+    /// What supplied the key, like "the link"
+    pub source: &'static str,
+    /// The key is not the default Notary key
+    pub differs: bool,
+}
+
+/// SHA-256 of the uncompressed SEC1 encoding of the key, hex encoded
+pub fn key_fingerprint(key: &p256::PublicKey) -> String {
+    hex(&Sha256::digest(key.to_encoded_point(false).as_bytes()))
+}
+
+/// Parse a `proof.json` and verify it against the Notary's public key.
+///
+/// `progress` is called before every step.
+pub fn verify_proof_file(
+    data: &[u8],
+    pem: p256::PublicKey,
+    progress: impl FnMut(Step),
+) -> ProofReport {
+    ProofReport {
+        proof_hash: content_hash(data),
+        notary: key_fingerprint(&pem),
+        result: verify(data, pem, progress),
+    }
+}
+
+fn verify(
+    data: &[u8],
+    pem: p256::PublicKey,
+    mut progress: impl FnMut(Step),
+) -> Result<VerifiedProof, VerificationError> {
+    progress(Step::Parsing);
+    // Explain the TLSNotary files that are often opened by mistake, rather than why they do
+    // not parse
+    let parse_error = |err: String| match identify(data) {
+        Some(artifact) => VerificationError::WrongFile(artifact),
+        None => VerificationError::Parse(err),
+    };
+    let json_str = std::str::from_utf8(data).map_err(|err| parse_error(err.to_string()))?;
+    let tls_proof: TlsProof =
+        serde_json::from_str(json_str).map_err(|err| parse_error(err.to_string()))?;
+
+    let TlsProof {
+        // The session proof establishes the identity of the server and the commitments
+        // to the TLS transcript.
+        session,
+        // The substrings proof proves select portions of the transcript, while redacting
+        // anything the Prover chose not to disclose.
+        substrings,
+    } = tls_proof;
+
+    // Verify the session proof against the Notary's public key
+    progress(Step::Session);
+    session
+        .verify_with_default_cert_verifier(pem)
+        .map_err(|err| VerificationError::Session(err.to_string()))?;
+
+    let SessionProof {
+        // The session header that was signed by the Notary is a succinct commitment to the TLS transcript.
+        header,
+        // This is the server name, checked against the certificate chain shared in the TLS handshake.
+        server_name,
+        ..
+    } = session;
+
+    // Verify the substrings proof against the session header.
+    // This returns the redacted transcripts
+    progress(Step::Substrings);
+    let (mut sent, mut recv) = substrings
+        .verify(&header)
+        .map_err(|err| VerificationError::Substrings(err.to_string()))?;
+
+    // Replace the bytes which the Prover chose not to disclose
+    for transcript in [&mut sent, &mut recv] {
+        transcript.set_redacted(REDACTED_BYTE);
+    }
+
+    let (openings, certificates) = serde_json::from_str(json_str)
+        .map(|proof| (read_openings(&proof), read_certificates(&proof)))
+        .unwrap_or_default();
+
+    Ok(VerifiedProof {
+        server_name: server_name.as_str().to_string(),
+        time: header.time(),
+        sent: Transcript {
+            bytes: sent.data().to_vec(),
+            redacted: sent.redacted().clone().iter_ranges().collect(),
+        },
+        recv: Transcript {
+            bytes: recv.data().to_vec(),
+            redacted: recv.redacted().clone().iter_ranges().collect(),
+        },
+        openings,
+        certificates,
+    })
+}
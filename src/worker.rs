@@ -0,0 +1,71 @@
+use gloo::worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+use crate::verify::{content_hash, verify_proof_file, ProofReport, Step, VerificationError};
+
+/// Path of the worker script built by trunk, see `index.html`.
+///
+/// Relative to the document base, so it is found when the app is served under a
+/// `--public-url` path rather than at the site root.
+pub const WORKER_PATH: &str = "./worker.js";
+
+/// Verifies proof files off the main thread, so the page stays responsive
+pub struct VerifierWorker;
+
+/// Verify a proof file
+#[derive(Serialize, Deserialize)]
+pub struct Request {
+    /// Id of the file in the app
+    pub id: usize,
+    pub data: Vec<u8>,
+    /// SEC1 encoding of the Notary public key
+    pub notary_key: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Response {
+    /// The verification of file `id` reached `step`
+    Progress {
+        id: usize,
+        step: Step,
+    },
+    Done {
+        id: usize,
+        report: ProofReport,
+    },
+}
+
+impl Worker for VerifierWorker {
+    type Message = ();
+    type Input = Request;
+    type Output = Response;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, request: Self::Input, who: HandlerId) {
+        let id = request.id;
+        let report = verify_request(&request, |step| {
+            scope.respond(who, Response::Progress { id, step })
+        });
+        scope.respond(who, Response::Done { id, report });
+    }
+}
+
+/// Verify the proof of a request, calling `progress` before every step
+pub fn verify_request(request: &Request, progress: impl FnMut(Step)) -> ProofReport {
+    match p256::PublicKey::from_sec1_bytes(&request.notary_key) {
+        Ok(pem) => verify_proof_file(&request.data, pem, progress),
+        Err(err) => ProofReport {
+            proof_hash: content_hash(&request.data),
+            notary: String::new(),
+            result: Err(VerificationError::Session(format!(
+                "Invalid Notary public key {}",
+                err
+            ))),
+        },
+    }
+}